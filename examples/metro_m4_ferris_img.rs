@@ -4,9 +4,9 @@
 extern crate panic_halt;
 extern crate metro_m4 as hal;
 
-use embedded_graphics::image::Image16BPP;
+use embedded_graphics::image::{Image, ImageRawLE};
+use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use embedded_graphics::primitives::rectangle::Rectangle;
 
 use hal::spi_master;
 use hal::prelude::*;
@@ -44,19 +44,18 @@ fn main() -> ! {
     let rst = pins.d1.into_push_pull_output(&mut pins.port);
     let mut delay = hal::delay::Delay::new(core.SYST, &mut clocks);
 
-    let mut disp = st7735_lcd::ST7735::new(spi, dc, rst, false, true);
+    let mut disp = st7735_lcd::ST7735::new(spi, dc, rst, false, true, 80, 160);
     disp.init(&mut delay).unwrap();
     disp.set_orientation(&Orientation::Landscape).unwrap();
     // My particular lcd seems to be off a few pixels
     disp.set_offset(1, 25);
 
-    let black_backdrop = Rectangle::new(Coord::new(0, 0), Coord::new(160, 80)).fill(Some(0x0000u16.into()));
+    disp.clear(Rgb565::BLACK).unwrap();
 
-    disp.draw(black_backdrop.into_iter());
-    
-    let ferris = Image16BPP::new(include_bytes!("./ferris.raw"), 86, 64).translate(Coord::new(40, 33));
-    
-    disp.draw(ferris.into_iter());
+    let raw: ImageRawLE<Rgb565> = ImageRawLE::new(include_bytes!("./ferris.raw"), 86);
+    let ferris = Image::new(&raw, Point::new(37, 8));
+
+    ferris.draw(&mut disp).unwrap();
 
     loop {}
 }
@@ -0,0 +1,253 @@
+//! A non-blocking driver for async executors such as Embassy.
+//!
+//! [`ST7735`] mirrors the core of the blocking driver on top of
+//! `embedded-hal-async`: bus transfers and the delays of `init` are
+//! awaited, so long frame transfers yield to other tasks, e.g. while DMA
+//! runs. Pixels are sent as they are, without the blocking driver's color
+//! transforms, and windows must lie on the panel.
+//!
+//! # Cancellation
+//!
+//! The drawing futures can be dropped at any await point, as `select` and
+//! timeouts do. The driver keeps no state about a transfer in progress:
+//! there is no `finish` step, and no pixel is held back between writes.
+//! A dropped future leaves the controller in RAM write mode with DC
+//! high, or with DC low if it was dropped while sending a command, and
+//! the pixels sent so far stay on the panel. Every drawing call starts by
+//! driving DC low and re-opening its window with CASET, RASET and RAMWR,
+//! so the next call draws where it should whatever was interrupted.
+
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::{encode, Error, Orientation, CHUNK_PIXELS};
+
+use num_traits::ToPrimitive;
+
+use embedded_hal_1::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::spi::SpiDevice;
+
+/// Error type of an async driver on device `SPI` with data/command pin `DC`.
+pub type AsyncError<SPI, DC> = Error<<SPI as embedded_hal_1::spi::ErrorType>::Error, <DC as embedded_hal_1::digital::ErrorType>::Error>;
+
+/// Async ST7735 driver.
+pub struct ST7735<SPI, DC, RST>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    spi: SPI,
+    dc: DC,
+    rst: RST,
+    rgb: bool,
+    inverted: bool,
+    dx: u16,
+    dy: u16,
+    width: u16,
+    height: u16,
+    /// Orientation last set, which `width` and `height` follow
+    orientation: Orientation,
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+    RST: OutputPin<Error = DC::Error>,
+{
+    /// Runs commands to initialize the display.
+    pub async fn init<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), AsyncError<SPI, DC>>
+    where
+        DELAY: DelayNs,
+    {
+        self.hard_reset()?;
+        self.write_command(Instruction::SWRESET, &[]).await?;
+        delay.delay_ms(200).await;
+        self.write_command(Instruction::SLPOUT, &[]).await?;
+        delay.delay_ms(200).await;
+        self.write_command(Instruction::FRMCTR1, &[0x01, 0x2C, 0x2D]).await?;
+        self.write_command(Instruction::FRMCTR2, &[0x01, 0x2C, 0x2D]).await?;
+        self.write_command(Instruction::FRMCTR3, &[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D]).await?;
+        self.write_command(Instruction::INVCTR, &[0x07]).await?;
+        self.write_command(Instruction::PWCTR1, &[0xA2, 0x02, 0x84]).await?;
+        self.write_command(Instruction::PWCTR2, &[0xC5]).await?;
+        self.write_command(Instruction::PWCTR3, &[0x0A, 0x00]).await?;
+        self.write_command(Instruction::PWCTR4, &[0x8A, 0x2A]).await?;
+        self.write_command(Instruction::PWCTR5, &[0x8A, 0xEE]).await?;
+        self.write_command(Instruction::VMCTR1, &[0x0E]).await?;
+        if self.inverted {
+            self.write_command(Instruction::INVON, &[]).await?;
+        } else {
+            self.write_command(Instruction::INVOFF, &[]).await?;
+        }
+        self.set_orientation(&Orientation::Portrait).await?;
+        self.write_command(Instruction::COLMOD, &[0x05]).await?;
+        self.write_command(Instruction::DISPON, &[]).await?;
+        delay.delay_ms(200).await;
+        Ok(())
+    }
+
+    /// Pulses the reset pin.
+    pub fn hard_reset(&mut self) -> Result<(), AsyncError<SPI, DC>> {
+        self.rst.set_high().map_err(Error::Rst)?;
+        self.rst.set_low().map_err(Error::Rst)?;
+        self.rst.set_high().map_err(Error::Rst)
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a new driver instance.
+    ///
+    /// The arguments are those of the blocking [`crate::ST7735::new`].
+    pub fn new(spi: SPI, dc: DC, rst: RST, rgb: bool, inverted: bool, width: u16, height: u16) -> Self {
+        ST7735 {
+            spi,
+            dc,
+            rst,
+            rgb,
+            inverted,
+            dx: 0,
+            dy: 0,
+            width,
+            height,
+            orientation: Orientation::Portrait,
+        }
+    }
+
+    /// Returns the panel size in pixels.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Sets the global offset of the displayed image
+    pub fn set_offset(&mut self, dx: u16, dy: u16) {
+        self.dx = dx;
+        self.dy = dy;
+    }
+
+    /// Sets the orientation, swapping the width and height reported by
+    /// `size` when switching between portrait and landscape.
+    pub async fn set_orientation(&mut self, orientation: &Orientation) -> Result<(), AsyncError<SPI, DC>> {
+        let mut madctl = orientation.to_u8().unwrap();
+        if !self.rgb {
+            madctl |= 0x08;
+        }
+        self.write_command(Instruction::MADCTL, &[madctl]).await?;
+        if orientation.is_landscape() != self.orientation.is_landscape() {
+            core::mem::swap(&mut self.width, &mut self.height);
+        }
+        self.orientation = *orientation;
+        Ok(())
+    }
+
+    /// Returns the orientation last set.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Sets a pixel color at the given coords, ignoring pixels off the panel.
+    ///
+    /// Cancellation safe; see the [module docs](self#cancellation).
+    pub async fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result<(), AsyncError<SPI, DC>> {
+        if x >= self.width || y >= self.height {
+            return Ok(());
+        }
+        self.set_address_window(x, y, x, y).await?;
+        self.write_command(Instruction::RAMWR, &[]).await?;
+        self.write_data(&color.to_be_bytes()).await
+    }
+
+    /// Sets pixel colors at the given drawing window
+    ///
+    /// Cancellation safe: dropping the future leaves the pixels sent so far
+    /// on the panel and the next call re-opens its own window; see the
+    /// [module docs](self#cancellation).
+    pub async fn set_pixels<P>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: P) -> Result<(), AsyncError<SPI, DC>>
+    where
+        P: IntoIterator<Item = u16>,
+    {
+        self.open_window(sx, sy, ex, ey).await?;
+        let mut bytes = [0u8; CHUNK_PIXELS * 2];
+        let mut len = 0;
+        for color in colors {
+            bytes[len..len + 2].copy_from_slice(&color.to_be_bytes());
+            len += 2;
+            if len == bytes.len() {
+                self.write_data(&bytes).await?;
+                len = 0;
+            }
+        }
+        if len > 0 {
+            self.write_data(&bytes[..len]).await?;
+        }
+        Ok(())
+    }
+
+    /// Pushes a rectangle of row-major colors with a single window setup.
+    ///
+    /// Fails without drawing anything unless `colors` holds exactly
+    /// `rect.width * rect.height` pixels. Cancellation safe like
+    /// [`ST7735::set_pixels`].
+    pub async fn flush_region(&mut self, rect: Region, colors: &[u16]) -> Result<(), AsyncError<SPI, DC>> {
+        if colors.len() != rect.width as usize * rect.height as usize {
+            return Err(Error::InvalidArgument);
+        }
+        if colors.is_empty() {
+            return Ok(());
+        }
+        let (ex, ey) = rect.corner().ok_or(Error::OutOfBounds)?;
+        self.open_window(rect.x, rect.y, ex, ey).await?;
+        let mut bytes = [0u8; CHUNK_PIXELS * 2];
+        for chunk in colors.chunks(CHUNK_PIXELS) {
+            let count = encode::to_be_bytes(chunk, &mut bytes);
+            self.write_data(&bytes[..count * 2]).await?;
+        }
+        Ok(())
+    }
+
+    /// Pushes a full frame of row-major colors with a single window setup.
+    ///
+    /// Fails without drawing anything unless `colors` holds exactly
+    /// `width * height` pixels of the current orientation. Cancellation
+    /// safe like [`ST7735::set_pixels`].
+    pub async fn flush(&mut self, colors: &[u16]) -> Result<(), AsyncError<SPI, DC>> {
+        self.flush_region(Region::new(0, 0, self.width, self.height), colors).await
+    }
+
+    /// Selects a window on the panel and starts writing to it.
+    async fn open_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), AsyncError<SPI, DC>> {
+        if sx > ex || sy > ey || ex >= self.width || ey >= self.height {
+            return Err(Error::OutOfBounds);
+        }
+        self.set_address_window(sx, sy, ex, ey).await?;
+        self.write_command(Instruction::RAMWR, &[]).await
+    }
+
+    async fn set_address_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), AsyncError<SPI, DC>> {
+        let offset = |coord: u16, delta: u16| coord.checked_add(delta).map(u16::to_be_bytes).ok_or(Error::OutOfBounds);
+        let (sx, ex) = (offset(sx, self.dx)?, offset(ex, self.dx)?);
+        let (sy, ey) = (offset(sy, self.dy)?, offset(ey, self.dy)?);
+        self.write_command(Instruction::CASET, &[sx[0], sx[1], ex[0], ex[1]]).await?;
+        self.write_command(Instruction::RASET, &[sy[0], sy[1], ey[0], ey[1]]).await
+    }
+
+    async fn write_command(&mut self, command: Instruction, params: &[u8]) -> Result<(), AsyncError<SPI, DC>> {
+        self.dc.set_low().map_err(Error::Dc)?;
+        self.spi.write(&[command.to_u8().unwrap()]).await.map_err(Error::Spi)?;
+        if !params.is_empty() {
+            self.write_data(params).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_data(&mut self, data: &[u8]) -> Result<(), AsyncError<SPI, DC>> {
+        self.dc.set_high().map_err(Error::Dc)?;
+        self.spi.write(data).await.map_err(Error::Spi)
+    }
+}
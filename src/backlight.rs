@@ -0,0 +1,122 @@
+//! Backlight control through a GPIO or PWM pin.
+
+use core::convert::TryFrom;
+
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::PwmPin;
+
+/// A backlight switched on and off by an output pin.
+pub struct Backlight<P: OutputPin> {
+    pin: P,
+    active_low: bool,
+    on: bool,
+}
+
+impl<P: OutputPin> Backlight<P> {
+    /// Wraps a pin that lights the backlight when high.
+    ///
+    /// The pin is left as it is until the backlight is first switched.
+    pub fn new(pin: P) -> Self {
+        Backlight {
+            pin,
+            active_low: false,
+            on: false,
+        }
+    }
+
+    /// Wraps a pin that lights the backlight when low, as on modules that
+    /// drive the LED through a PNP transistor.
+    pub fn active_low(pin: P) -> Self {
+        Backlight {
+            pin,
+            active_low: true,
+            on: false,
+        }
+    }
+
+    /// Switches the backlight on or off.
+    pub fn set(&mut self, on: bool) -> Result<(), P::Error> {
+        if on != self.active_low {
+            self.pin.set_high()?;
+        } else {
+            self.pin.set_low()?;
+        }
+        self.on = on;
+        Ok(())
+    }
+
+    /// Switches the backlight on.
+    pub fn on(&mut self) -> Result<(), P::Error> {
+        self.set(true)
+    }
+
+    /// Switches the backlight off.
+    pub fn off(&mut self) -> Result<(), P::Error> {
+        self.set(false)
+    }
+
+    /// Returns whether the backlight was last switched on.
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Returns the pin.
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+/// A backlight dimmed by a PWM channel.
+///
+/// Brightness goes from 0 (off) to 255 (full duty cycle) and maps
+/// linearly onto the channel's duty range. The levels returned by
+/// [`BrightnessRamp::tick`](crate::ramp::BrightnessRamp::tick) can be
+/// passed straight to [`PwmBacklight::set_brightness`].
+pub struct PwmBacklight<P: PwmPin> {
+    pwm: P,
+    brightness: u8,
+}
+
+impl<P> PwmBacklight<P>
+where
+    P: PwmPin,
+    P::Duty: Into<u32> + TryFrom<u32>,
+{
+    /// Enables the channel at full brightness.
+    pub fn new(pwm: P) -> Self {
+        let mut backlight = PwmBacklight { pwm, brightness: 0 };
+        backlight.pwm.enable();
+        backlight.set_brightness(u8::MAX);
+        backlight
+    }
+
+    /// Sets the brightness, 0 being off.
+    pub fn set_brightness(&mut self, brightness: u8) {
+        let max: u32 = self.pwm.get_max_duty().into();
+        let duty = (max as u64 * brightness as u64 / u8::MAX as u64) as u32;
+        let duty = P::Duty::try_from(duty).unwrap_or_else(|_| self.pwm.get_max_duty());
+        self.pwm.set_duty(duty);
+        self.brightness = brightness;
+    }
+
+    /// Returns the brightness last set.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Switches the backlight on at full brightness.
+    pub fn on(&mut self) {
+        self.set_brightness(u8::MAX);
+    }
+
+    /// Switches the backlight off.
+    pub fn off(&mut self) {
+        self.set_brightness(0);
+    }
+
+    /// Disables the channel and returns it.
+    pub fn release(mut self) -> P {
+        self.pwm.disable();
+        self.pwm
+    }
+}
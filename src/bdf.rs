@@ -0,0 +1,252 @@
+//! Compile-time conversion of BDF bitmap fonts into [`Font`]s.
+//!
+//! The parser runs in `const` context, so a font file is turned into a
+//! glyph table while the crate using it compiles and malformed files are
+//! compile errors. Use it through [`include_bdf!`](crate::include_bdf).
+//!
+//! Glyphs are placed into cells of the font bounding box, so the result is
+//! fixed width even if the BDF font is not. Only characters in the
+//! requested range are kept; missing ones are left blank.
+
+use crate::font::Font;
+
+/// Size of a font as it will be laid out by [`glyphs`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Metrics {
+    /// Cell width in pixels.
+    pub width: u8,
+
+    /// Cell height in pixels.
+    pub height: u8,
+
+    /// First character kept.
+    pub first: char,
+
+    /// Last character kept.
+    pub last: char,
+}
+
+impl Metrics {
+    /// Returns the length of the glyph table in bytes.
+    pub const fn table_len(&self) -> usize {
+        let row_bytes = (self.width as usize).div_ceil(8);
+        (self.last as usize - self.first as usize + 1) * row_bytes * self.height as usize
+    }
+
+    /// Returns a [`Font`] with these metrics drawing from `glyphs`.
+    pub const fn font(&self, glyphs: &'static [u8]) -> Font {
+        Font {
+            width: self.width,
+            height: self.height,
+            first: self.first,
+            last: self.last,
+            glyphs,
+        }
+    }
+}
+
+/// Reads the metrics of the BDF font in `src` for the characters
+/// `first..=last`.
+pub const fn metrics(src: &str, first: char, last: char) -> Metrics {
+    if first as u32 > last as u32 {
+        panic!("empty character range");
+    }
+    let (width, height, _, _) = bounding_box(src.as_bytes());
+    if width <= 0 || height <= 0 || width > u8::MAX as i32 || height > u8::MAX as i32 {
+        panic!("unsupported FONTBOUNDINGBOX");
+    }
+    Metrics {
+        width: width as u8,
+        height: height as u8,
+        first,
+        last,
+    }
+}
+
+/// Renders the characters `first..=last` of the BDF font in `src` into a
+/// glyph table in the [`Font`] format.
+///
+/// `N` must equal [`Metrics::table_len`] for the same arguments.
+pub const fn glyphs<const N: usize>(src: &str, first: char, last: char) -> [u8; N] {
+    let metrics = metrics(src, first, last);
+    if N != metrics.table_len() {
+        panic!("glyph table length does not match the font metrics");
+    }
+    let src = src.as_bytes();
+    let (width, height, x_offset, y_offset) = bounding_box(src);
+    let row_bytes = (width as usize).div_ceil(8);
+    let glyph_size = row_bytes * height as usize;
+
+    let mut out = [0u8; N];
+    // Encoding and BBX of the current glyph, and the next bitmap row
+    let mut encoding: i32 = -1;
+    let mut bbx = (0, 0, 0, 0);
+    let mut bitmap_row: i32 = -1;
+
+    let mut pos = 0;
+    while pos < src.len() {
+        let (start, end, next) = line(src, pos);
+        pos = next;
+        if is_keyword(src, start, end, b"ENCODING") {
+            encoding = field(src, start, end, 1);
+        } else if is_keyword(src, start, end, b"BBX") {
+            bbx = (
+                field(src, start, end, 1),
+                field(src, start, end, 2),
+                field(src, start, end, 3),
+                field(src, start, end, 4),
+            );
+        } else if is_keyword(src, start, end, b"BITMAP") {
+            bitmap_row = 0;
+        } else if is_keyword(src, start, end, b"ENDCHAR") {
+            bitmap_row = -1;
+        } else if bitmap_row >= 0 {
+            let (glyph_width, glyph_height, glyph_x, glyph_y) = bbx;
+            let cell_row = (y_offset + height - 1) - (glyph_y + glyph_height - 1) + bitmap_row;
+            if encoding >= first as i32 && encoding <= last as i32 && cell_row >= 0 && cell_row < height {
+                let base = (encoding as usize - first as usize) * glyph_size + cell_row as usize * row_bytes;
+                let mut px = 0;
+                while px < glyph_width {
+                    let cell_x = glyph_x - x_offset + px;
+                    if cell_x >= 0 && cell_x < width && bit(src, start, end, px as usize) {
+                        out[base + cell_x as usize / 8] |= 0x80 >> (cell_x as usize % 8);
+                    }
+                    px += 1;
+                }
+            }
+            bitmap_row += 1;
+        }
+    }
+    out
+}
+
+/// Converts a BDF font file into a [`Font`](crate::font::Font) at compile
+/// time.
+///
+/// The path is relative to the file invoking the macro. Only printable
+/// ASCII is kept unless a character range is given.
+///
+/// ```ignore
+/// const TERMINUS: Font = st7735_lcd::include_bdf!("../fonts/ter-u12n.bdf");
+/// const DIGITS: Font = st7735_lcd::include_bdf!("../fonts/ter-u12n.bdf", '0', '9');
+/// ```
+#[macro_export]
+macro_rules! include_bdf {
+    ($path:expr) => {
+        $crate::include_bdf!($path, ' ', '~')
+    };
+    ($path:expr, $first:expr, $last:expr) => {{
+        const SRC: &str = include_str!($path);
+        const METRICS: $crate::bdf::Metrics = $crate::bdf::metrics(SRC, $first, $last);
+        const GLYPHS: [u8; METRICS.table_len()] = $crate::bdf::glyphs(SRC, $first, $last);
+        METRICS.font(&GLYPHS)
+    }};
+}
+
+/// Returns width, height and x and y offset of the font bounding box.
+const fn bounding_box(src: &[u8]) -> (i32, i32, i32, i32) {
+    let mut pos = 0;
+    while pos < src.len() {
+        let (start, end, next) = line(src, pos);
+        if is_keyword(src, start, end, b"FONTBOUNDINGBOX") {
+            return (
+                field(src, start, end, 1),
+                field(src, start, end, 2),
+                field(src, start, end, 3),
+                field(src, start, end, 4),
+            );
+        }
+        pos = next;
+    }
+    panic!("no FONTBOUNDINGBOX in BDF font");
+}
+
+/// Returns the bounds of the line starting at `pos` without its line
+/// ending, and where the next line starts.
+const fn line(src: &[u8], pos: usize) -> (usize, usize, usize) {
+    let mut end = pos;
+    while end < src.len() && src[end] != b'\n' {
+        end += 1;
+    }
+    let next = end + 1;
+    if end > pos && src[end - 1] == b'\r' {
+        end -= 1;
+    }
+    (pos, end, next)
+}
+
+/// Returns whether the line starts with `keyword` as a whole word.
+const fn is_keyword(src: &[u8], start: usize, end: usize, keyword: &[u8]) -> bool {
+    if end - start < keyword.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < keyword.len() {
+        if src[start + i] != keyword[i] {
+            return false;
+        }
+        i += 1;
+    }
+    start + i == end || src[start + i] == b' '
+}
+
+/// Parses the whitespace separated integer `index` of the line, counting
+/// the keyword as 0.
+const fn field(src: &[u8], start: usize, end: usize, index: usize) -> i32 {
+    let mut pos = start;
+    let mut current = 0;
+    loop {
+        while pos < end && src[pos] == b' ' {
+            pos += 1;
+        }
+        if pos == end {
+            panic!("missing field in BDF font");
+        }
+        if current == index {
+            break;
+        }
+        while pos < end && src[pos] != b' ' {
+            pos += 1;
+        }
+        current += 1;
+    }
+
+    let negative = src[pos] == b'-';
+    if negative {
+        pos += 1;
+    }
+    let mut value: i32 = 0;
+    let mut digits = 0;
+    while pos < end && src[pos] != b' ' {
+        let digit = src[pos];
+        if !digit.is_ascii_digit() {
+            panic!("malformed number in BDF font");
+        }
+        value = value * 10 + (digit - b'0') as i32;
+        digits += 1;
+        pos += 1;
+    }
+    if digits == 0 {
+        panic!("malformed number in BDF font");
+    }
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Returns whether pixel `x` of a hex encoded bitmap row is set.
+const fn bit(src: &[u8], start: usize, end: usize, x: usize) -> bool {
+    let digit = start + x / 4;
+    if digit >= end {
+        return false;
+    }
+    let value = match src[digit] {
+        b @ b'0'..=b'9' => b - b'0',
+        b @ b'a'..=b'f' => b - b'a' + 10,
+        b @ b'A'..=b'F' => b - b'A' + 10,
+        _ => panic!("malformed bitmap row in BDF font"),
+    };
+    value & (0x8 >> (x % 4)) != 0
+}
@@ -0,0 +1,114 @@
+//! Estimating how long transfers take, for planning frame budgets.
+//!
+//! The driver never sees the bus clock, so estimates are computed from a
+//! [`BusTiming`] describing it. They count every byte the driver sends
+//! for a window, including the window setup, plus a fixed cost for each
+//! bus write to cover chip select, DC switching and driver overhead.
+
+use crate::region::Region;
+use crate::CHUNK_PIXELS;
+
+use core::time::Duration;
+
+/// Bytes sent to set up a window: CASET, RASET and RAMWR with parameters.
+const SETUP_BYTES: u64 = 11;
+
+/// Bus writes needed to set up a window.
+const SETUP_WRITES: u64 = 7;
+
+/// Pixel format on the bus, also found at the crate root.
+pub use crate::PixelFormat;
+
+/// Speed and overheads of the bus the panel is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BusTiming {
+    /// SPI clock in Hz.
+    pub spi_hz: u32,
+
+    /// Pixel format sent.
+    pub format: PixelFormat,
+
+    /// Largest number of pixel bytes sent in one bus write.
+    pub chunk_bytes: u32,
+
+    /// Fixed cost of every bus write in nanoseconds.
+    pub write_overhead_ns: u32,
+}
+
+/// Returns the bytes the driver sends per bus write in `format`.
+const fn driver_chunk_bytes(format: PixelFormat) -> u32 {
+    format.bytes(CHUNK_PIXELS as u64) as u32
+}
+
+impl BusTiming {
+    /// Describes a bus running at `spi_hz`, sending RGB565 pixels in the
+    /// driver's chunks with no overhead per write.
+    pub const fn new(spi_hz: u32) -> Self {
+        BusTiming {
+            spi_hz,
+            format: PixelFormat::Rgb565,
+            chunk_bytes: driver_chunk_bytes(PixelFormat::Rgb565),
+            write_overhead_ns: 0,
+        }
+    }
+
+    /// Sets the pixel format sent, along with the size of the driver's
+    /// chunks in that format: 48 bytes for RGB444, 64 for RGB565 and 96
+    /// for RGB666. Set [`BusTiming::chunk_bytes`] afterwards to override.
+    pub const fn format(mut self, format: PixelFormat) -> Self {
+        self.format = format;
+        self.chunk_bytes = driver_chunk_bytes(format);
+        self
+    }
+
+    /// Sets the largest number of pixel bytes sent in one bus write.
+    pub const fn chunk_bytes(mut self, chunk_bytes: u32) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+
+    /// Sets the fixed cost of every bus write.
+    pub const fn write_overhead_ns(mut self, write_overhead_ns: u32) -> Self {
+        self.write_overhead_ns = write_overhead_ns;
+        self
+    }
+
+    /// Estimates how long filling `window` takes, window setup included.
+    pub fn estimate_transfer(&self, window: Region) -> Duration {
+        let pixels = window.width as u64 * window.height as u64;
+        if pixels == 0 {
+            return Duration::ZERO;
+        }
+        let data = self.format.bytes(pixels);
+        let writes = SETUP_WRITES + data.div_ceil(self.chunk_bytes.max(1) as u64);
+        self.duration(SETUP_BYTES + data, writes)
+    }
+
+    /// Estimates how long filling every window of `windows` takes.
+    pub fn estimate_frame(&self, windows: &[Region]) -> Duration {
+        windows.iter().map(|&window| self.estimate_transfer(window)).sum()
+    }
+
+    /// Returns how many times per second `window` can be filled, leaving
+    /// no time for anything else.
+    pub fn max_refresh_hz(&self, window: Region) -> u32 {
+        let nanos = self.estimate_transfer(window).as_nanos();
+        if nanos == 0 {
+            return u32::MAX;
+        }
+        (1_000_000_000 / nanos).min(u32::MAX as u128) as u32
+    }
+
+    /// Returns whether `window` can be filled `hz` times per second.
+    pub fn fits(&self, window: Region, hz: u32) -> bool {
+        self.max_refresh_hz(window) >= hz
+    }
+
+    fn duration(&self, bytes: u64, writes: u64) -> Duration {
+        let hz = self.spi_hz.max(1) as u128;
+        let bits = bytes as u128 * 8;
+        let nanos = (bits * 1_000_000_000).div_ceil(hz) + writes as u128 * self.write_overhead_ns as u128;
+        Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+    }
+}
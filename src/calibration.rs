@@ -0,0 +1,99 @@
+//! Interactive panel offset calibration.
+
+use crate::scroll::FRAME_LINES;
+use crate::{DisplayError, ST7735};
+
+use core::iter::repeat_n;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Side length of the square markers in each corner, in pixels.
+const MARKER: u16 = 3;
+
+/// Shows a test pattern while the panel offset is adjusted.
+///
+/// The pattern is a 1 pixel border around the panel with a filled square in
+/// every corner. The offset is right once all four edges and corners are
+/// fully visible and no stray lines show. Feed the result of
+/// [`Calibration::finish`] into [`ST7735::set_offset`] on later boots.
+pub struct Calibration<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    color: u16,
+    background: u16,
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws the calibration pattern at the current offset.
+    pub fn calibrate(&mut self, color: u16, background: u16) -> Result<Calibration<'_, SPI, DC, RST>, DisplayError<SPI, DC>> {
+        let mut calibration = Calibration { display: self, color, background };
+        calibration.draw_pattern(color)?;
+        Ok(calibration)
+    }
+}
+
+impl<'a, SPI, DC, RST> Calibration<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Moves the image by `(dx, dy)` pixels and redraws the pattern.
+    ///
+    /// The old pattern is erased first so only the current one shows.
+    pub fn nudge(&mut self, dx: i16, dy: i16) -> Result<(), DisplayError<SPI, DC>> {
+        let shift = |offset: u16, delta: i16| (offset as i32 + delta as i32).clamp(0, FRAME_LINES as i32) as u16;
+        let (x, y) = self.current_offset();
+        let (x, y) = (shift(x, dx), shift(y, dy));
+        if (x, y) == self.current_offset() {
+            return Ok(());
+        }
+        self.draw_pattern(self.background)?;
+        self.display.set_offset(x, y);
+        self.draw_pattern(self.color)
+    }
+
+    /// Returns the offset being tried.
+    pub fn current_offset(&self) -> (u16, u16) {
+        (self.display.dx, self.display.dy)
+    }
+
+    /// Erases the pattern and returns the chosen offset, which stays set.
+    pub fn finish(mut self) -> Result<(u16, u16), DisplayError<SPI, DC>> {
+        self.draw_pattern(self.background)?;
+        Ok(self.current_offset())
+    }
+
+    fn draw_pattern(&mut self, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let (width, height) = self.display.size();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (ex, ey) = (width - 1, height - 1);
+        self.fill(0, 0, ex, 0, color)?;
+        self.fill(0, ey, ex, ey, color)?;
+        self.fill(0, 0, 0, ey, color)?;
+        self.fill(ex, 0, ex, ey, color)?;
+
+        let size = MARKER.min(width).min(height);
+        for (x, y) in [(0, 0), (width - size, 0), (0, height - size), (width - size, height - size)] {
+            self.fill(x, y, x + size - 1, y + size - 1, color)?;
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let count = (ex - sx + 1) as usize * (ey - sy + 1) as usize;
+        self.display.set_pixels(sx, sy, ex, ey, repeat_n(color, count))
+    }
+}
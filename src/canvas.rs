@@ -0,0 +1,96 @@
+//! Windowed drawing of `embedded-canvas` canvases.
+
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+#[cfg(feature = "canvas-alloc")]
+use embedded_canvas::CanvasAt;
+use embedded_canvas::CCanvasAt;
+use embedded_graphics_core::geometry::{Dimensions, Point};
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565};
+use embedded_graphics_core::primitives::Rectangle;
+
+/// A canvas that has been placed on the display.
+pub trait PlacedCanvas {
+    /// Returns the area of the display the canvas covers.
+    fn area(&self) -> Rectangle;
+
+    /// Returns the color at a display point, `None` where transparent.
+    fn pixel(&self, point: Point) -> Option<Rgb565>;
+}
+
+impl<const W: usize, const H: usize> PlacedCanvas for CCanvasAt<Rgb565, W, H> {
+    fn area(&self) -> Rectangle {
+        self.bounding_box()
+    }
+
+    fn pixel(&self, point: Point) -> Option<Rgb565> {
+        self.get_pixel(point)
+    }
+}
+
+#[cfg(feature = "canvas-alloc")]
+impl PlacedCanvas for CanvasAt<Rgb565> {
+    fn area(&self) -> Rectangle {
+        self.bounding_box()
+    }
+
+    fn pixel(&self, point: Point) -> Option<Rgb565> {
+        self.get_pixel(point)
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws a placed canvas, clipping anything left of or above the panel.
+    ///
+    /// A fully opaque canvas goes out as a single windowed transfer. When
+    /// the canvas has transparent pixels each row is sent as one window per
+    /// opaque run, leaving whatever is underneath the gaps untouched.
+    pub fn draw_canvas<C: PlacedCanvas>(&mut self, canvas: &C) -> Result<(), DisplayError<SPI, DC>> {
+        let area = canvas.area();
+        let bottom_right = match area.bottom_right() {
+            Some(point) => point,
+            None => return Ok(()),
+        };
+        let sx = area.top_left.x.max(0);
+        let sy = area.top_left.y.max(0);
+        let ex = bottom_right.x.min(u16::MAX as i32);
+        let ey = bottom_right.y.min(u16::MAX as i32);
+        if sx > ex || sy > ey {
+            return Ok(());
+        }
+
+        let opaque = (sy..=ey).all(|y| (sx..=ex).all(|x| canvas.pixel(Point::new(x, y)).is_some()));
+        if opaque {
+            let colors = (sy..=ey).flat_map(|y| (sx..=ex).map(move |x| Point::new(x, y)))
+                .map(|point| canvas.pixel(point).map_or(0, |color| color.into_storage()));
+            return self.set_pixels(sx as u16, sy as u16, ex as u16, ey as u16, colors);
+        }
+
+        for y in sy..=ey {
+            let mut x = sx;
+            while x <= ex {
+                if canvas.pixel(Point::new(x, y)).is_none() {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < ex && canvas.pixel(Point::new(x + 1, y)).is_some() {
+                    x += 1;
+                }
+                let colors = (start..=x)
+                    .map(|x| canvas.pixel(Point::new(x, y)).map_or(0, |color| color.into_storage()));
+                self.set_pixels(start as u16, y as u16, x as u16, y as u16, colors)?;
+                x += 1;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+//! Recording the exact bus traffic of driver calls and replaying it.
+//!
+//! A driver built on [`CaptureSpi`], [`CaptureDc`] and
+//! [`NoResetPin`](crate::NoResetPin) records
+//! everything it would send into a byte buffer, and [`CaptureDelay`] adds
+//! the delays of [`ST7735::init`]. The stream is a sequence of records:
+//!
+//! - `0x00 byte`: a command
+//! - `0x01 len_lo len_hi bytes...`: `len` data bytes
+//! - `0x02 ms`: a delay
+//!
+//! [`ST7735::replay`] sends a recorded stream to a real panel.
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, MadctlFlags, Orientation, PixelFormat, ST7735};
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+const COMMAND: u8 = 0x00;
+const DATA: u8 = 0x01;
+const DELAY: u8 = 0x02;
+
+/// The recording buffer ran out of space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CaptureFull;
+
+/// Bus traffic recorded so far.
+pub struct Capture<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    dc: bool,
+    /// Start of the data record being extended, if the last record is one
+    data: Option<usize>,
+}
+
+impl<'a> Capture<'a> {
+    /// Creates an empty recording into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Capture {
+            buf,
+            len: 0,
+            dc: false,
+            data: None,
+        }
+    }
+
+    /// Returns the recorded stream.
+    pub fn stream(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Discards everything recorded.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.data = None;
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Result<(), CaptureFull> {
+        let end = self.len + bytes.len();
+        self.buf.get_mut(self.len..end).ok_or(CaptureFull)?.copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CaptureFull> {
+        if !self.dc {
+            self.data = None;
+            for &byte in bytes {
+                self.push(&[COMMAND, byte])?;
+            }
+            return Ok(());
+        }
+        for &byte in bytes {
+            let start = match self.data {
+                Some(start) if u16::from_le_bytes([self.buf[start + 1], self.buf[start + 2]]) < u16::MAX => start,
+                _ => {
+                    let start = self.len;
+                    self.push(&[DATA, 0, 0])?;
+                    self.data = Some(start);
+                    start
+                }
+            };
+            self.push(&[byte])?;
+            let len = u16::from_le_bytes([self.buf[start + 1], self.buf[start + 2]]) + 1;
+            self.buf[start + 1..start + 3].copy_from_slice(&len.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    fn delay(&mut self, ms: u8) -> Result<(), CaptureFull> {
+        self.data = None;
+        self.push(&[DELAY, ms])
+    }
+}
+
+/// SPI bus recording into a [`Capture`].
+pub struct CaptureSpi<'a, 'b>(pub &'a RefCell<Capture<'b>>);
+
+impl<'a, 'b> spi::Write<u8> for CaptureSpi<'a, 'b> {
+    type Error = CaptureFull;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), CaptureFull> {
+        self.0.borrow_mut().write(words)
+    }
+}
+
+/// Data/command pin recording into a [`Capture`].
+pub struct CaptureDc<'a, 'b>(pub &'a RefCell<Capture<'b>>);
+
+impl<'a, 'b> OutputPin for CaptureDc<'a, 'b> {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().dc = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().dc = true;
+        Ok(())
+    }
+}
+
+/// Delay recording into a [`Capture`] instead of waiting.
+///
+/// A full buffer is noticed by the next bus write.
+pub struct CaptureDelay<'a, 'b>(pub &'a RefCell<Capture<'b>>);
+
+impl<'a, 'b> DelayMs<u8> for CaptureDelay<'a, 'b> {
+    fn delay_ms(&mut self, ms: u8) {
+        let _ = self.0.borrow_mut().delay(ms);
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sends a stream recorded with [`Capture`] to the panel.
+    ///
+    /// The driver follows the MADCTL and COLMOD commands in the stream, so
+    /// size, orientation, offsets and pixel format match the panel
+    /// afterwards. Other state the stream changes, such as sleep or
+    /// scrolling, is not tracked. Fails with [`Error::InvalidArgument`] on
+    /// a malformed stream, after sending the records before it.
+    pub fn replay<DELAY>(&mut self, stream: &[u8], delay: &mut DELAY) -> Result<(), DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.finish_pixels()?;
+        let madctl = Instruction::MADCTL.to_u8().unwrap();
+        let colmod = Instruction::COLMOD.to_u8().unwrap();
+        let mut last_command = None;
+        let mut rest = stream;
+        while let Some((&kind, tail)) = rest.split_first() {
+            match kind {
+                COMMAND => {
+                    let (&command, tail) = tail.split_first().ok_or(Error::InvalidArgument)?;
+                    self.command_seq = self.command_seq.wrapping_add(1);
+                    self.dc.set_low().map_err(Error::Dc)?;
+                    self.bus_write(&[command], false)?;
+                    last_command = Some(command);
+                    rest = tail;
+                }
+                DATA => {
+                    if tail.len() < 2 {
+                        return Err(Error::InvalidArgument);
+                    }
+                    let len = u16::from_le_bytes([tail[0], tail[1]]) as usize;
+                    let data = tail.get(2..2 + len).ok_or(Error::InvalidArgument)?;
+                    if let (Some(command), Some(&value)) = (last_command.take(), data.first()) {
+                        if command == madctl {
+                            self.madctl = value;
+                            self.follow_madctl_value(value);
+                        } else if command == colmod {
+                            if let Some(format) = colmod_format(value) {
+                                self.format = format;
+                            }
+                        }
+                    }
+                    self.write_data(data)?;
+                    rest = &tail[2 + len..];
+                }
+                DELAY => {
+                    let (&ms, tail) = tail.split_first().ok_or(Error::InvalidArgument)?;
+                    delay.delay_ms(ms);
+                    rest = tail;
+                }
+                _ => return Err(Error::InvalidArgument),
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes on a MADCTL value replayed from a stream.
+    ///
+    /// The orientation is the one whose MY, MX and MV bits match, or the
+    /// current one if none does, with the remaining bits as flags.
+    fn follow_madctl_value(&mut self, value: u8) {
+        const ALL: [Orientation; 4] = [
+            Orientation::Portrait,
+            Orientation::Landscape,
+            Orientation::PortraitSwapped,
+            Orientation::LandscapeSwapped,
+        ];
+        let bits = value & !0x08;
+        let orientation = ALL.iter().copied().find(|o| o.to_u8() == Some(bits & 0xE0)).unwrap_or(self.orientation);
+        self.rgb = value & 0x08 == 0;
+        self.follow_madctl(orientation, MadctlFlags(bits ^ orientation.to_u8().unwrap()));
+    }
+}
+
+/// Returns the format a COLMOD parameter selects, if it is one.
+fn colmod_format(colmod: u8) -> Option<PixelFormat> {
+    match colmod & 0x07 {
+        0x03 => Some(PixelFormat::Rgb444),
+        0x05 => Some(PixelFormat::Rgb565),
+        0x06 => Some(PixelFormat::Rgb666),
+        _ => None,
+    }
+}
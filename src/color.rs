@@ -0,0 +1,121 @@
+//! Color transforms applied to every pixel on its way to the panel.
+
+/// Returns the approximate luma (0-255) of an RGB565 color.
+pub fn luma(color: u16) -> u8 {
+    let r = ((color >> 11) & 0x1F) as u32 * 255 / 31;
+    let g = ((color >> 5) & 0x3F) as u32 * 255 / 63;
+    let b = (color & 0x1F) as u32 * 255 / 31;
+    ((r * 77 + g * 150 + b * 29) >> 8) as u8
+}
+
+/// Packs 18-bit color as read back from the panel, one byte per channel
+/// with the value in the upper six bits, into RGB565.
+pub fn rgb666_to_rgb565(r: u8, g: u8, b: u8) -> u16 {
+    ((r as u16 & 0xF8) << 8) | ((g as u16 & 0xFC) << 3) | (b as u16 >> 3)
+}
+
+/// High-contrast accessibility scheme.
+///
+/// Every color is replaced by `dark` or `light` depending on whether its
+/// luma is below `threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HighContrast {
+    /// Luma (0-255) from which colors are considered light.
+    pub threshold: u8,
+
+    /// Color used for dark input.
+    pub dark: u16,
+
+    /// Color used for light input.
+    pub light: u16,
+}
+
+impl HighContrast {
+    /// Creates a scheme mapping to `dark` and `light` around `threshold`.
+    pub const fn new(threshold: u8, dark: u16, light: u16) -> Self {
+        HighContrast { threshold, dark, light }
+    }
+
+    /// Maps a single color through the scheme.
+    pub fn apply(&self, color: u16) -> u16 {
+        if luma(color) < self.threshold {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+}
+
+impl Default for HighContrast {
+    /// White on black, split at mid grey.
+    fn default() -> Self {
+        HighContrast::new(128, 0x0000, 0xFFFF)
+    }
+}
+
+/// Night mode, warming the output by scaling down blue and green.
+///
+/// The scales are fractions of 256, so `256` leaves a channel alone and
+/// `0` removes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NightMode {
+    /// Scale applied to the green channel.
+    pub green: u16,
+
+    /// Scale applied to the blue channel.
+    pub blue: u16,
+}
+
+impl NightMode {
+    /// Creates a night mode with explicit channel scales.
+    pub const fn new(green: u16, blue: u16) -> Self {
+        NightMode { green, blue }
+    }
+
+    /// Creates a night mode from a single warmth level.
+    ///
+    /// `0` leaves colors unchanged and `255` all but removes blue while
+    /// keeping two thirds of the green.
+    pub const fn warmth(level: u8) -> Self {
+        let level = level as u16;
+        NightMode::new(256 - level / 3, 256 - level)
+    }
+
+    /// Maps a single color through the night mode.
+    pub fn apply(&self, color: u16) -> u16 {
+        let r = color & 0xF800;
+        let g = (((color >> 5) & 0x3F) as u32 * self.green.min(256) as u32 / 256) as u16;
+        let b = ((color & 0x1F) as u32 * self.blue.min(256) as u32 / 256) as u16;
+        r | (g << 5) | b
+    }
+}
+
+/// A user-supplied transform applied to every pixel written.
+#[derive(Clone, Copy)]
+pub enum ColorTransform {
+    /// Calls the function for every pixel.
+    Fn(fn(u16) -> u16),
+
+    /// Looks every pixel up in a table indexed by the RGB565 value.
+    ///
+    /// This costs 128 KiB but is faster than all but the simplest
+    /// functions; see [`fill_lut`].
+    Lut(&'static [u16; 65536]),
+}
+
+impl ColorTransform {
+    /// Maps a single color through the transform.
+    pub fn apply(&self, color: u16) -> u16 {
+        match self {
+            ColorTransform::Fn(f) => f(color),
+            ColorTransform::Lut(lut) => lut[color as usize],
+        }
+    }
+}
+
+/// Fills a lookup table for [`ColorTransform::Lut`] from a function.
+pub fn fill_lut(lut: &mut [u16; 65536], f: fn(u16) -> u16) {
+    for (color, entry) in lut.iter_mut().enumerate() {
+        *entry = f(color as u16);
+    }
+}
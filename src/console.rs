@@ -0,0 +1,191 @@
+//! A scrolling text terminal on the whole panel.
+
+use crate::font::{TextStyle, FONT_6X8};
+use crate::scroll::FRAME_LINES;
+use crate::{DisplayError, MadctlFlags, Orientation, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Prints text line by line in a fixed-width font, for use with
+/// `write!` and `writeln!`.
+///
+/// Lines wrap at the right edge of the panel. Once the last line is full
+/// the terminal scrolls up by a line. In portrait orientation without
+/// mirroring this uses hardware scrolling, so only the new line is drawn;
+/// in any other orientation output continues at the top instead, and the
+/// line after the newest one is kept blank.
+///
+/// The first drawing error makes every later write fail, and can be
+/// taken with [`Terminal::take_error`]. The scroll offset is left as it
+/// is when the terminal is dropped; [`Terminal::clear`] resets it.
+pub struct Terminal<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    style: TextStyle,
+    background: u16,
+    cols: u16,
+    rows: u16,
+    /// Cursor position, with `row` counting visible lines from the top
+    col: u16,
+    row: u16,
+    /// Text line of the panel shown at the top
+    top: u16,
+    hardware_scroll: bool,
+    error: Option<DisplayError<SPI, DC>>,
+}
+
+impl<'a, SPI, DC, RST> Terminal<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a terminal printing white 6x8 text on black and clears the
+    /// panel.
+    pub fn new(display: &'a mut ST7735<SPI, DC, RST>) -> Result<Self, DisplayError<SPI, DC>> {
+        Self::with_style(display, TextStyle::new(&FONT_6X8, 0xFFFF).background(0x0000))
+    }
+
+    /// Creates a terminal printing in `style` and clears the panel.
+    ///
+    /// The background of `style` is used for the text cells and for
+    /// blanking lines, and defaults to black.
+    pub fn with_style(display: &'a mut ST7735<SPI, DC, RST>, style: TextStyle) -> Result<Self, DisplayError<SPI, DC>> {
+        let background = style.background.unwrap_or(0x0000);
+        let (width, height) = display.size();
+        let (cw, ch) = (style.font.width as u16, style.font.height as u16);
+        let (cols, rows) = if cw == 0 || ch == 0 { (0, 0) } else { (width / cw, height / ch) };
+        let hardware_scroll = display.orientation() == Orientation::Portrait
+            && !display.madctl_flags().contains(MadctlFlags::MY)
+            && !display.madctl_flags().contains(MadctlFlags::MV)
+            && rows > 0
+            && display.dy as u32 + rows as u32 * ch as u32 <= FRAME_LINES as u32;
+        let mut terminal = Terminal {
+            display,
+            style: style.background(background),
+            background,
+            cols,
+            rows,
+            col: 0,
+            row: 0,
+            top: 0,
+            hardware_scroll,
+            error: None,
+        };
+        terminal.clear()?;
+        Ok(terminal)
+    }
+
+    /// Returns the number of columns and lines of text that fit.
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    /// Returns the column and visible line the next character goes to.
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.col, self.row)
+    }
+
+    /// Blanks the panel, undoes any scrolling and moves the cursor to the
+    /// top left.
+    pub fn clear(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let (width, height) = self.display.size();
+        if width > 0 && height > 0 {
+            self.display.fill_window(0, 0, width - 1, height - 1, self.background)?;
+        }
+        if self.hardware_scroll {
+            let scrolled = self.rows * self.style.font.height as u16;
+            let top_fixed = self.display.dy;
+            self.display.set_scroll_area(top_fixed, scrolled, FRAME_LINES - top_fixed - scrolled)?;
+            self.display.set_scroll_offset(top_fixed)?;
+        }
+        self.col = 0;
+        self.row = 0;
+        self.top = 0;
+        Ok(())
+    }
+
+    /// Prints `text`, handling `\n` and `\r`.
+    pub fn print(&mut self, text: &str) -> Result<(), DisplayError<SPI, DC>> {
+        if self.cols == 0 {
+            return Ok(());
+        }
+        for c in text.chars() {
+            match c {
+                '\n' => self.new_line()?,
+                '\r' => self.col = 0,
+                c => {
+                    if self.col == self.cols {
+                        self.new_line()?;
+                    }
+                    let (x, y) = (self.col * self.style.font.width as u16, self.line_y(self.row));
+                    self.display.draw_char(x, y, c, &self.style)?;
+                    self.col += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the drawing error that stopped the terminal, if any.
+    pub fn take_error(&mut self) -> Option<DisplayError<SPI, DC>> {
+        self.error.take()
+    }
+
+    /// Returns the panel row at which visible line `row` is drawn.
+    fn line_y(&self, row: u16) -> u16 {
+        (row + self.top) % self.rows * self.style.font.height as u16
+    }
+
+    /// Moves the cursor to the start of the next line, scrolling if the
+    /// last line was reached.
+    fn new_line(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        self.col = 0;
+        let ch = self.style.font.height as u16;
+        if self.hardware_scroll {
+            if self.row + 1 < self.rows {
+                self.row += 1;
+                return Ok(());
+            }
+            self.top = (self.top + 1) % self.rows;
+            self.blank_line(self.line_y(self.row))?;
+            let top_fixed = self.display.dy;
+            return self.display.set_scroll_offset(top_fixed + self.top * ch);
+        }
+        self.row = (self.row + 1) % self.rows;
+        self.blank_line(self.line_y(self.row))?;
+        if self.rows > 1 {
+            self.blank_line(self.line_y((self.row + 1) % self.rows))?;
+        }
+        Ok(())
+    }
+
+    /// Fills the text line at panel row `y` with the background.
+    fn blank_line(&mut self, y: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let (width, _) = self.display.size();
+        let ch = self.style.font.height as u16;
+        self.display.fill_window(0, y, width - 1, y + ch - 1, self.background)
+    }
+}
+
+impl<'a, SPI, DC, RST> core::fmt::Write for Terminal<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.error.is_some() {
+            return Err(core::fmt::Error);
+        }
+        self.print(s).map_err(|e| {
+            self.error = Some(e);
+            core::fmt::Error
+        })
+    }
+}
@@ -0,0 +1,61 @@
+//! Chip select handling for shared SPI buses.
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Error of a [`WithCs`] bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CsError<SpiE, PinE> {
+    /// The SPI bus reported an error.
+    Spi(SpiE),
+
+    /// Setting the chip select pin failed.
+    Cs(PinE),
+}
+
+/// An SPI bus that asserts an active-low chip select around every write.
+///
+/// Pass it to [`ST7735::new`](crate::ST7735::new) in place of the bus, so
+/// the display only listens while the driver is writing to it and other
+/// devices on the bus are left alone. Chip select is released again even
+/// if the write fails.
+///
+/// Reads need chip select held across the command and the answer, so
+/// this wrapper is write-only.
+pub struct WithCs<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+}
+
+impl<SPI, CS> WithCs<SPI, CS>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+{
+    /// Wraps `spi`, releasing chip select first.
+    pub fn new(spi: SPI, mut cs: CS) -> Result<Self, CsError<SPI::Error, CS::Error>> {
+        cs.set_high().map_err(CsError::Cs)?;
+        Ok(WithCs { spi, cs })
+    }
+
+    /// Returns the bus and the chip select pin.
+    pub fn into_inner(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI, CS> spi::Write<u8> for WithCs<SPI, CS>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+{
+    type Error = CsError<SPI::Error, CS::Error>;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(CsError::Cs)?;
+        let result = self.spi.write(words).map_err(CsError::Spi);
+        self.cs.set_high().map_err(CsError::Cs)?;
+        result
+    }
+}
@@ -0,0 +1,189 @@
+//! Double buffering with optional tearing-effect synchronization.
+
+use crate::instruction::Instruction;
+use crate::strip::{StripBuffers, StripSink};
+use crate::{DisplayError, Error, PixelFormat, TearingEffect, ST7735};
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+/// Two full frame buffers: one being drawn, one matching the panel.
+///
+/// Drawing goes to the back buffer only. [`DoubleBuffer::swap`] sends the
+/// rows of the back buffer that differ from the panel and then exchanges
+/// the buffers. The panel keeps refreshing meanwhile and may show a frame
+/// that is partly old, partly new; [`DoubleBuffer::swap_vsync`] times
+/// the transfer so that it does not. After a swap the back buffer holds
+/// the frame before last and must be redrawn completely, as is usual for
+/// animations.
+pub struct DoubleBuffer<'a> {
+    front: &'a mut [u16],
+    back: &'a mut [u16],
+    width: u16,
+    height: u16,
+    tearing_effect: bool,
+    /// Whether a transfer started by `swap_buffers` may still be running
+    in_flight: bool,
+}
+
+impl<'a> DoubleBuffer<'a> {
+    /// Creates double buffering for a `width` x `height` panel.
+    ///
+    /// Both buffers must hold at least `width * height` pixels and `front`
+    /// should match what is on the panel, e.g. by clearing both first.
+    pub fn new(front: &'a mut [u16], back: &'a mut [u16], width: u16, height: u16) -> Self {
+        let len = width as usize * height as usize;
+        assert!(front.len() >= len && back.len() >= len);
+        DoubleBuffer {
+            front,
+            back,
+            width,
+            height,
+            tearing_effect: false,
+            in_flight: false,
+        }
+    }
+
+    /// Returns the back buffer, row-major.
+    pub fn back_mut(&mut self) -> &mut [u16] {
+        self.back
+    }
+
+    /// Sets a pixel color in the back buffer, clipping to the panel.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+        if x < self.width && y < self.height {
+            self.back[y as usize * self.width as usize + x as usize] = color;
+        }
+    }
+
+    /// Fills the back buffer with `color`.
+    pub fn clear(&mut self, color: u16) {
+        let len = self.width as usize * self.height as usize;
+        for pixel in self.back[..len].iter_mut() {
+            *pixel = color;
+        }
+    }
+
+    /// Shows the back buffer and starts drawing into the other one.
+    pub fn swap<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let stride = self.width as usize;
+        for y in 0..self.height as usize {
+            let row = y * stride..(y + 1) * stride;
+            let (back, front) = (&self.back[row.clone()], &self.front[row]);
+            let first = match back.iter().zip(front).position(|(b, f)| b != f) {
+                Some(first) => first,
+                None => continue,
+            };
+            let last = back.iter().zip(front).rposition(|(b, f)| b != f).unwrap_or(first);
+            display.set_address_window(first as u16, y as u16, last as u16, y as u16)?;
+            display.write_command(Instruction::RAMWR, None)?;
+            display.write_pixel_slice(&back[first..=last])?;
+            display.finish_pixels()?;
+        }
+        core::mem::swap(&mut self.front, &mut self.back);
+        Ok(())
+    }
+
+    /// Shows the back buffer by handing it to `sink` strip by strip, and
+    /// returns while the last strip may still be in flight.
+    ///
+    /// The frame is encoded into `strips` one strip while the other is
+    /// being sent. The next frame can be drawn into the new back buffer
+    /// meanwhile, but `strips` must be left alone until the transfer is
+    /// done. The next call waits for it first; call [`DoubleBuffer::wait`]
+    /// before using the display for anything else. Only RGB565 is
+    /// supported; other pixel formats, and strips too short for a pixel,
+    /// fail with [`Error::InvalidArgument`].
+    pub fn swap_buffers<SPI, DC, RST, S>(
+        &mut self,
+        display: &mut ST7735<SPI, DC, RST>,
+        strips: &mut StripBuffers,
+        sink: &mut S,
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+        S: StripSink<Error = <SPI as spi::Write<u8>>::Error>,
+    {
+        self.wait(sink).map_err(Error::Spi)?;
+        if display.format != PixelFormat::Rgb565 || !strips.hold_a_pixel() {
+            return Err(Error::InvalidArgument);
+        }
+        let len = self.width as usize * self.height as usize;
+        if len == 0 {
+            return Ok(());
+        }
+        display.set_address_window(0, 0, self.width - 1, self.height - 1)?;
+        display.write_command(Instruction::RAMWR, None)?;
+        display.dc.set_high().map_err(Error::Dc)?;
+        let back = &self.back[..len];
+        let fill = |index: usize, out: &mut [u16]| {
+            let count = out.len().min(len - index);
+            out[..count].copy_from_slice(&back[index..index + count]);
+            count
+        };
+        self.in_flight = display.send_strips(len, strips, sink, fill)?;
+        core::mem::swap(&mut self.front, &mut self.back);
+        Ok(())
+    }
+
+    /// Waits for a transfer started by [`DoubleBuffer::swap_buffers`].
+    pub fn wait<S: StripSink>(&mut self, sink: &mut S) -> Result<(), S::Error> {
+        if self.in_flight {
+            sink.wait()?;
+            self.in_flight = false;
+        }
+        Ok(())
+    }
+
+    /// Like [`DoubleBuffer::swap`], but starts sending at the panel's next
+    /// vertical blank as signalled on the TE pin.
+    ///
+    /// The first call enables the controller's tearing effect output. The
+    /// transfer then starts right behind the refresh, so the panel never
+    /// shows a half-drawn frame as long as the changed rows are sent faster
+    /// than the panel scans.
+    ///
+    /// TE is polled every microsecond. If no vertical blank starts within
+    /// `timeout_us`, e.g. because the pin is not wired, nothing is sent
+    /// and the call fails with [`Error::Busy`]. A refresh takes about
+    /// 16 ms, so a timeout of twice that is plenty.
+    pub fn swap_vsync<SPI, DC, RST, TE, DELAY>(
+        &mut self,
+        display: &mut ST7735<SPI, DC, RST>,
+        te: &mut TE,
+        delay: &mut DELAY,
+        timeout_us: u32,
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+        TE: InputPin<Error = <DC as OutputPin>::Error>,
+        DELAY: DelayUs<u32>,
+    {
+        if !self.tearing_effect {
+            display.set_tearing_effect(TearingEffect::Vertical)?;
+            self.tearing_effect = true;
+        }
+        let mut left = timeout_us;
+        let mut poll = |want_high: bool| -> Result<(), DisplayError<SPI, DC>> {
+            while te.is_high().map_err(Error::Te)? != want_high {
+                left = left.checked_sub(1).ok_or(Error::Busy)?;
+                delay.delay_us(1);
+            }
+            Ok(())
+        };
+        // Let a blank in progress pass, then wait for the next to begin.
+        poll(false)?;
+        poll(true)?;
+        self.swap(display)
+    }
+}
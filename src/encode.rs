@@ -0,0 +1,30 @@
+//! Encoding of pixel data into the byte order expected by the panel.
+
+/// Encodes pixels as big-endian bytes, the order the panel expects.
+///
+/// Converts as many pixels as fit into `out` and returns how many that
+/// was. The result is the same whatever the endianness of the target.
+pub fn to_be_bytes(pixels: &[u16], out: &mut [u8]) -> usize {
+    let count = pixels.len().min(out.len() / 2);
+    for (pixel, bytes) in pixels[..count].iter().zip(out.chunks_exact_mut(2)) {
+        bytes.copy_from_slice(&pixel.to_be_bytes());
+    }
+    count
+}
+
+/// Reduces an RGB565 pixel to the 12 bits of RGB444.
+pub fn to_rgb444(color: u16) -> u16 {
+    let r = color >> 12;
+    let g = (color >> 7) & 0x0F;
+    let b = (color >> 1) & 0x0F;
+    (r << 8) | (g << 4) | b
+}
+
+/// Expands an RGB565 pixel to the three bytes of RGB666, each channel in
+/// the upper six bits of its byte.
+pub fn to_rgb666_bytes(color: u16) -> [u8; 3] {
+    let r = (color >> 11) as u8;
+    let g = ((color >> 5) & 0x3F) as u8;
+    let b = (color & 0x1F) as u8;
+    [(r << 3) | ((r >> 4) << 2), g << 2, (b << 3) | ((b >> 4) << 2)]
+}
@@ -0,0 +1,129 @@
+//! Incremental flushing of pixel buffers without an async runtime.
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Number of pixels sent per `poll_flush` call unless configured otherwise.
+pub const DEFAULT_CHUNK: usize = 256;
+
+/// A transfer of a pixel buffer into a drawing window, sent piece by piece.
+///
+/// Create one with [`Flush::new`] and keep calling
+/// [`ST7735::poll_flush`] until it stops returning `WouldBlock`.
+///
+/// A flush can be abandoned at any point: dropping it leaves the
+/// controller in RAM write mode, which the next command ends, and the data
+/// already sent stays on the panel. Other drawing calls may also be made
+/// between polls; the flush notices and re-opens its window at the first
+/// unsent pixel.
+pub struct Flush<'a> {
+    pixels: &'a [u16],
+    window: (u16, u16, u16, u16),
+    chunk: usize,
+    pos: usize,
+    /// Position up to which the currently open window is valid
+    open_until: usize,
+    /// Command sequence number right after our RAMWR
+    command_seq: Option<u32>,
+}
+
+impl<'a> Flush<'a> {
+    /// Prepares a transfer of `pixels` into the window `(sx, sy)..=(ex, ey)`.
+    ///
+    /// Pixels are written row by row. Extra pixels beyond the window area
+    /// are ignored. A window whose start lies past its end is empty, like
+    /// with `set_pixels`, so the flush is done at once.
+    pub fn new(sx: u16, sy: u16, ex: u16, ey: u16, pixels: &'a [u16]) -> Self {
+        let area = window_width(sx, ex) * window_width(sy, ey);
+        Flush {
+            pixels: &pixels[..pixels.len().min(area)],
+            window: (sx, sy, ex, ey),
+            chunk: DEFAULT_CHUNK,
+            pos: 0,
+            open_until: 0,
+            command_seq: None,
+        }
+    }
+
+    /// Sets how many pixels each poll may send at most.
+    pub fn chunk_size(mut self, pixels: usize) -> Self {
+        self.chunk = pixels.max(1);
+        self
+    }
+
+    /// Returns the number of pixels still to be sent.
+    pub fn remaining(&self) -> usize {
+        self.pixels.len() - self.pos
+    }
+
+    /// Returns whether every pixel has been sent.
+    pub fn is_done(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// Returns the number of columns (or rows) from `start` to `end`
+/// inclusive, zero if `start` is past `end`.
+fn window_width(start: u16, end: u16) -> usize {
+    match end.checked_sub(start) {
+        Some(span) => span as usize + 1,
+        None => 0,
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sends the next chunk of a [`Flush`].
+    ///
+    /// Returns `WouldBlock` while pixels remain, so super-loop firmware
+    /// can do other work between calls.
+    pub fn poll_flush(&mut self, flush: &mut Flush) -> nb::Result<(), DisplayError<SPI, DC>> {
+        if flush.is_done() {
+            return Ok(());
+        }
+        if flush.command_seq != Some(self.command_seq) || flush.pos == flush.open_until {
+            self.open_flush_window(flush)?;
+        }
+        let end = (flush.pos + flush.chunk).min(flush.open_until);
+        if let Err(e) = self.write_pixel_slice(&flush.pixels[flush.pos..end]) {
+            // The controller may have seen part of the chunk, so start
+            // over with a fresh window on the next poll.
+            flush.command_seq = None;
+            return Err(nb::Error::Other(e));
+        }
+        flush.pos = end;
+        if flush.is_done() {
+            self.finish_pixels().map_err(nb::Error::Other)
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Opens a window starting at the first unsent pixel of `flush`.
+    ///
+    /// When resuming in the middle of a row only the rest of that row can
+    /// be covered, the remaining rows get their own window afterwards.
+    fn open_flush_window(&mut self, flush: &mut Flush) -> Result<(), DisplayError<SPI, DC>> {
+        let (sx, sy, ex, ey) = flush.window;
+        let width = window_width(sx, ex);
+        let x = sx + (flush.pos % width) as u16;
+        let y = sy + (flush.pos / width) as u16;
+        if x == sx {
+            self.set_address_window(sx, y, ex, ey)?;
+            flush.open_until = flush.pixels.len();
+        } else {
+            self.set_address_window(x, y, ex, y)?;
+            flush.open_until = (flush.pos + window_width(x, ex)).min(flush.pixels.len());
+        }
+        self.write_command(Instruction::RAMWR, None)?;
+        flush.command_seq = Some(self.command_seq);
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+//! Bitmap fonts and text drawing.
+
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A fixed-width bitmap font.
+///
+/// Glyphs cover the characters `first..=last` and are stored one after the
+/// other, `height` rows each. Every row takes `(width + 7) / 8` bytes with
+/// the leftmost pixel in the most significant bit.
+#[derive(Clone, Copy, Debug)]
+pub struct Font {
+    /// Glyph width (and advance) in pixels.
+    pub width: u8,
+
+    /// Glyph height (and line height) in pixels.
+    pub height: u8,
+
+    /// First character in the glyph table.
+    pub first: char,
+
+    /// Last character in the glyph table.
+    pub last: char,
+
+    /// Glyph bitmaps.
+    pub glyphs: &'static [u8],
+}
+
+impl Font {
+    /// Returns the number of bytes per glyph row.
+    pub const fn row_bytes(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+
+    /// Returns the bitmap of `c`, or of `'?'` if the font lacks it.
+    pub fn glyph(&self, c: char) -> &'static [u8] {
+        let c = if (self.first..=self.last).contains(&c) { c } else { '?' };
+        let size = self.row_bytes() * self.height as usize;
+        let start = (c as usize).saturating_sub(self.first as usize) * size;
+        self.glyphs.get(start..start + size).unwrap_or(&[])
+    }
+
+    /// Returns whether pixel `(x, y)` of a glyph bitmap is set.
+    pub fn pixel(&self, glyph: &[u8], x: u8, y: u8) -> bool {
+        let byte = y as usize * self.row_bytes() + x as usize / 8;
+        glyph.get(byte).is_some_and(|b| b & (0x80 >> (x % 8)) != 0)
+    }
+
+    /// Returns how far drawing `c` moves the pen, in pixels.
+    ///
+    /// Fonts are fixed width, so this is the same for every character,
+    /// including ones drawn as the `'?'` fallback.
+    pub fn advance(&self, _c: char) -> u16 {
+        self.width as u16
+    }
+
+    /// Returns the longest prefix of `text` that fits into `max_width`
+    /// pixels.
+    pub fn fit<'t>(&self, text: &'t str, max_width: u16) -> &'t str {
+        let mut width = 0u16;
+        for (i, c) in text.char_indices() {
+            width = width.saturating_add(self.advance(c));
+            if width > max_width {
+                return &text[..i];
+            }
+        }
+        text
+    }
+}
+
+/// Size of a piece of text, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Size {
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Returns the size `text` takes when drawn with [`ST7735::draw_text`].
+pub fn measure_text(text: &str, style: &TextStyle) -> Size {
+    let font = style.font;
+    let width = text.chars().fold(0u16, |width, c| width.saturating_add(font.advance(c)));
+    Size {
+        width,
+        height: font.height as u16,
+    }
+}
+
+/// 6x8 pixel font covering printable ASCII.
+///
+/// The glyphs are taken from the embedded-graphics 6x8 font.
+pub const FONT_6X8: Font = Font {
+    width: 6,
+    height: 8,
+    first: ' ',
+    last: '~',
+    glyphs: &GLYPHS_6X8,
+};
+
+#[rustfmt::skip]
+const GLYPHS_6X8: [u8; 95 * 8] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,  // ' '
+    0x20, 0x20, 0x20, 0x20, 0x20, 0x00, 0x20, 0x00,  // '!'
+    0x48, 0x48, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00,  // '"'
+    0x50, 0x50, 0xF8, 0x50, 0xF8, 0x50, 0x50, 0x00,  // '#'
+    0x20, 0x78, 0xA0, 0x70, 0x28, 0xF0, 0x20, 0x00,  // '$'
+    0xC0, 0xC8, 0x10, 0x20, 0x40, 0x98, 0x18, 0x00,  // '%'
+    0x60, 0x90, 0xA0, 0x40, 0xA8, 0x90, 0x68, 0x00,  // '&'
+    0x60, 0x20, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00,  // "'"
+    0x10, 0x20, 0x40, 0x40, 0x40, 0x20, 0x10, 0x00,  // '('
+    0x40, 0x20, 0x10, 0x10, 0x10, 0x20, 0x40, 0x00,  // ')'
+    0x00, 0x50, 0x20, 0xF8, 0x20, 0x50, 0x00, 0x00,  // '*'
+    0x00, 0x20, 0x20, 0xF8, 0x20, 0x20, 0x00, 0x00,  // '+'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x60, 0x20, 0x40,  // ','
+    0x00, 0x00, 0x00, 0xF8, 0x00, 0x00, 0x00, 0x00,  // '-'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x60, 0x60, 0x00,  // '.'
+    0x00, 0x08, 0x10, 0x20, 0x40, 0x80, 0x00, 0x00,  // '/'
+    0x70, 0x88, 0x98, 0xA8, 0xC8, 0x88, 0x70, 0x00,  // '0'
+    0x20, 0x60, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00,  // '1'
+    0x70, 0x88, 0x08, 0x30, 0x40, 0x80, 0xF8, 0x00,  // '2'
+    0x70, 0x88, 0x08, 0x30, 0x08, 0x88, 0x70, 0x00,  // '3'
+    0x10, 0x30, 0x50, 0x90, 0xF8, 0x10, 0x10, 0x00,  // '4'
+    0xF8, 0x80, 0xF0, 0x08, 0x08, 0x88, 0x70, 0x00,  // '5'
+    0x30, 0x40, 0x80, 0xF0, 0x88, 0x88, 0x70, 0x00,  // '6'
+    0xF8, 0x08, 0x10, 0x20, 0x40, 0x40, 0x40, 0x00,  // '7'
+    0x70, 0x88, 0x88, 0x70, 0x88, 0x88, 0x70, 0x00,  // '8'
+    0x70, 0x88, 0x88, 0x78, 0x08, 0x10, 0x60, 0x00,  // '9'
+    0x00, 0x60, 0x60, 0x00, 0x60, 0x60, 0x00, 0x00,  // ':'
+    0x00, 0x00, 0x60, 0x60, 0x00, 0x60, 0x20, 0x40,  // ';'
+    0x10, 0x20, 0x40, 0x80, 0x40, 0x20, 0x10, 0x00,  // '<'
+    0x00, 0x00, 0xF8, 0x00, 0xF8, 0x00, 0x00, 0x00,  // '='
+    0x80, 0x40, 0x20, 0x10, 0x20, 0x40, 0x80, 0x00,  // '>'
+    0x70, 0x88, 0x08, 0x10, 0x20, 0x00, 0x20, 0x00,  // '?'
+    0x70, 0x88, 0x08, 0x68, 0xA8, 0xA8, 0x70, 0x00,  // '@'
+    0x70, 0x88, 0x88, 0xF8, 0x88, 0x88, 0x88, 0x00,  // 'A'
+    0xF0, 0x88, 0x88, 0xF0, 0x88, 0x88, 0xF0, 0x00,  // 'B'
+    0x70, 0x88, 0x80, 0x80, 0x80, 0x88, 0x70, 0x00,  // 'C'
+    0xE0, 0x90, 0x88, 0x88, 0x88, 0x90, 0xE0, 0x00,  // 'D'
+    0xF8, 0x80, 0x80, 0xF0, 0x80, 0x80, 0xF8, 0x00,  // 'E'
+    0xF8, 0x80, 0x80, 0xF0, 0x80, 0x80, 0x80, 0x00,  // 'F'
+    0x70, 0x88, 0x80, 0x80, 0x98, 0x88, 0x78, 0x00,  // 'G'
+    0x88, 0x88, 0x88, 0xF8, 0x88, 0x88, 0x88, 0x00,  // 'H'
+    0x70, 0x20, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00,  // 'I'
+    0x38, 0x10, 0x10, 0x10, 0x10, 0x90, 0x60, 0x00,  // 'J'
+    0x88, 0x90, 0xA0, 0xC0, 0xA0, 0x90, 0x88, 0x00,  // 'K'
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF8, 0x00,  // 'L'
+    0x88, 0xD8, 0xA8, 0xA8, 0x88, 0x88, 0x88, 0x00,  // 'M'
+    0x88, 0x88, 0xC8, 0xA8, 0x98, 0x88, 0x88, 0x00,  // 'N'
+    0x70, 0x88, 0x88, 0x88, 0x88, 0x88, 0x70, 0x00,  // 'O'
+    0xF0, 0x88, 0x88, 0xF0, 0x80, 0x80, 0x80, 0x00,  // 'P'
+    0x70, 0x88, 0x88, 0x88, 0xA8, 0x90, 0x68, 0x00,  // 'Q'
+    0xF0, 0x88, 0x88, 0xF0, 0xA0, 0x90, 0x88, 0x00,  // 'R'
+    0x70, 0x88, 0x80, 0x70, 0x08, 0x88, 0x70, 0x00,  // 'S'
+    0xF8, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00,  // 'T'
+    0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x70, 0x00,  // 'U'
+    0x88, 0x88, 0x88, 0x88, 0x88, 0x50, 0x20, 0x00,  // 'V'
+    0x88, 0x88, 0x88, 0xA8, 0xA8, 0xA8, 0x50, 0x00,  // 'W'
+    0x88, 0x88, 0x50, 0x20, 0x50, 0x88, 0x88, 0x00,  // 'X'
+    0x88, 0x88, 0x88, 0x50, 0x20, 0x20, 0x20, 0x00,  // 'Y'
+    0xF8, 0x08, 0x10, 0x20, 0x40, 0x80, 0xF8, 0x00,  // 'Z'
+    0x70, 0x40, 0x40, 0x40, 0x40, 0x40, 0x70, 0x00,  // '['
+    0x00, 0x80, 0x40, 0x20, 0x10, 0x08, 0x00, 0x00,  // '\\'
+    0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x70, 0x00,  // ']'
+    0x20, 0x50, 0x88, 0x00, 0x00, 0x00, 0x00, 0x00,  // '^'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8,  // '_'
+    0x40, 0x20, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,  // '`'
+    0x00, 0x00, 0x70, 0x08, 0x78, 0x88, 0x78, 0x00,  // 'a'
+    0x80, 0x80, 0xB0, 0xC8, 0x88, 0x88, 0xF0, 0x00,  // 'b'
+    0x00, 0x00, 0x70, 0x80, 0x80, 0x88, 0x70, 0x00,  // 'c'
+    0x08, 0x08, 0x68, 0x98, 0x88, 0x88, 0x78, 0x00,  // 'd'
+    0x00, 0x00, 0x70, 0x88, 0xF8, 0x80, 0x70, 0x00,  // 'e'
+    0x30, 0x48, 0x40, 0xE0, 0x40, 0x40, 0x40, 0x00,  // 'f'
+    0x00, 0x00, 0x78, 0x88, 0x88, 0x78, 0x08, 0x70,  // 'g'
+    0x80, 0x80, 0xB0, 0xC8, 0x88, 0x88, 0x88, 0x00,  // 'h'
+    0x20, 0x00, 0x60, 0x20, 0x20, 0x20, 0x70, 0x00,  // 'i'
+    0x10, 0x00, 0x30, 0x10, 0x10, 0x10, 0x90, 0x60,  // 'j'
+    0x80, 0x80, 0x90, 0xA0, 0xC0, 0xA0, 0x90, 0x00,  // 'k'
+    0x60, 0x20, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00,  // 'l'
+    0x00, 0x00, 0xD0, 0xA8, 0x88, 0x88, 0x88, 0x00,  // 'm'
+    0x00, 0x00, 0xB0, 0xC8, 0x88, 0x88, 0x88, 0x00,  // 'n'
+    0x00, 0x00, 0x70, 0x88, 0x88, 0x88, 0x70, 0x00,  // 'o'
+    0x00, 0x00, 0xF0, 0x88, 0x88, 0xF0, 0x80, 0x80,  // 'p'
+    0x00, 0x00, 0x78, 0x88, 0x88, 0x78, 0x08, 0x08,  // 'q'
+    0x00, 0x00, 0xB0, 0xC8, 0x80, 0x80, 0x80, 0x00,  // 'r'
+    0x00, 0x00, 0x78, 0x80, 0x70, 0x08, 0xF0, 0x00,  // 's'
+    0x40, 0x40, 0xE0, 0x40, 0x40, 0x48, 0x30, 0x00,  // 't'
+    0x00, 0x00, 0x88, 0x88, 0x88, 0x98, 0x68, 0x00,  // 'u'
+    0x00, 0x00, 0x88, 0x88, 0x88, 0x50, 0x20, 0x00,  // 'v'
+    0x00, 0x00, 0x88, 0x88, 0xA8, 0xA8, 0x50, 0x00,  // 'w'
+    0x00, 0x00, 0x88, 0x50, 0x20, 0x50, 0x88, 0x00,  // 'x'
+    0x00, 0x00, 0x88, 0x88, 0x88, 0x78, 0x08, 0x70,  // 'y'
+    0x00, 0x00, 0xF8, 0x10, 0x20, 0x40, 0xF8, 0x00,  // 'z'
+    0x10, 0x20, 0x20, 0x40, 0x20, 0x20, 0x10, 0x00,  // '{'
+    0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00,  // '|'
+    0x40, 0x20, 0x20, 0x10, 0x20, 0x20, 0x40, 0x00,  // '}'
+    0x68, 0x90, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,  // '~'
+];
+
+/// Font and colors used to draw text.
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    /// Font to draw with.
+    pub font: &'static Font,
+
+    /// Color of the glyph pixels.
+    pub color: u16,
+
+    /// Color of the remaining pixels of each glyph cell, or `None` to leave
+    /// them untouched.
+    pub background: Option<u16>,
+}
+
+impl TextStyle {
+    /// Creates a style drawing `font` in `color` on a transparent background.
+    pub const fn new(font: &'static Font, color: u16) -> Self {
+        TextStyle {
+            font,
+            color,
+            background: None,
+        }
+    }
+
+    /// Fills the glyph cells with `color`.
+    pub const fn background(mut self, color: u16) -> Self {
+        self.background = Some(color);
+        self
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws a single character with its top left corner at `(x, y)`.
+    ///
+    /// With a background color the whole cell is sent as one window,
+    /// otherwise each horizontal run of set pixels gets its own window.
+    /// Characters whose cell reaches past `u16::MAX` are skipped.
+    pub fn draw_char(&mut self, x: u16, y: u16, c: char, style: &TextStyle) -> Result<(), DisplayError<SPI, DC>> {
+        let font = style.font;
+        let glyph = font.glyph(c);
+        let (width, height) = (font.width, font.height);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (ex, ey) = match (x.checked_add(width as u16 - 1), y.checked_add(height as u16 - 1)) {
+            (Some(ex), Some(ey)) => (ex, ey),
+            _ => return Ok(()),
+        };
+
+        if let Some(background) = style.background {
+            let colors = (0..height).flat_map(|gy| (0..width).map(move |gx| (gx, gy)))
+                .map(|(gx, gy)| if font.pixel(glyph, gx, gy) { style.color } else { background });
+            return self.set_pixels(x, y, ex, ey, colors);
+        }
+
+        for gy in 0..height {
+            let mut gx = 0;
+            while gx < width {
+                if !font.pixel(glyph, gx, gy) {
+                    gx += 1;
+                    continue;
+                }
+                let start = gx;
+                while gx < width && font.pixel(glyph, gx, gy) {
+                    gx += 1;
+                }
+                let count = (gx - start) as usize;
+                let (sx, py) = (x + start as u16, y + gy as u16);
+                self.set_pixels(sx, py, sx + count as u16 - 1, py, core::iter::repeat_n(style.color, count))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a single line of text starting at `(x, y)`.
+    ///
+    /// Returns the x coordinate just past the last character.
+    pub fn draw_text(&mut self, x: u16, y: u16, text: &str, style: &TextStyle) -> Result<u16, DisplayError<SPI, DC>> {
+        let mut x = x;
+        for c in text.chars() {
+            self.draw_char(x, y, c, style)?;
+            x = x.saturating_add(style.font.width as u16);
+        }
+        Ok(x)
+    }
+}
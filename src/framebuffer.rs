@@ -0,0 +1,265 @@
+//! Drawing into RAM and sending whole frames at once.
+
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A drawing target backed by a caller-provided RGB565 frame buffer.
+///
+/// Drawing only touches the buffer, so it costs no bus traffic at all.
+/// [`FrameBuffer::flush`] then pushes the bounding box of everything
+/// changed since the last flush with a single window setup, which is much
+/// faster than drawing text or other scattered pixels straight to the
+/// panel.
+pub struct FrameBuffer<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    buffer: &'a mut [u16],
+    width: u16,
+    height: u16,
+    /// Bounding box `(sx, sy, ex, ey)` of pixels not yet sent
+    dirty: Option<(u16, u16, u16, u16)>,
+}
+
+impl<'a, SPI, DC, RST> FrameBuffer<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a frame buffer covering the panel at its current size.
+    ///
+    /// `buffer` must hold at least `width * height` pixels, row-major. The
+    /// whole buffer is sent by the first flush.
+    pub fn new(display: &'a mut ST7735<SPI, DC, RST>, buffer: &'a mut [u16]) -> Self {
+        let (width, height) = display.size();
+        assert!(buffer.len() >= width as usize * height as usize);
+        let mut framebuffer = FrameBuffer {
+            display,
+            buffer,
+            width,
+            height,
+            dirty: None,
+        };
+        framebuffer.mark_all_dirty();
+        framebuffer
+    }
+
+    /// Returns the frame buffer contents.
+    pub fn buffer(&self) -> &[u16] {
+        self.buffer
+    }
+
+    /// Returns the frame buffer contents for drawing into directly.
+    ///
+    /// The whole buffer is sent by the next flush.
+    pub fn buffer_mut(&mut self) -> &mut [u16] {
+        self.mark_all_dirty();
+        self.buffer
+    }
+
+    /// Returns the bounding box of the pixels changed since the last
+    /// flush, if any.
+    pub fn dirty(&self) -> Option<Region> {
+        self.dirty.map(|(sx, sy, ex, ey)| Region::new(sx, sy, (ex - sx).saturating_add(1), (ey - sy).saturating_add(1)))
+    }
+
+    /// Returns the panel size the buffer covers.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Fills the buffer with `color`.
+    pub fn clear(&mut self, color: u16) {
+        let len = self.width as usize * self.height as usize;
+        for pixel in &mut self.buffer[..len] {
+            *pixel = color;
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Sets a pixel color in the buffer, clipping to the panel.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+        if x < self.width && y < self.height {
+            self.buffer[y as usize * self.width as usize + x as usize] = color;
+            self.mark_dirty(x, y, x, y);
+        }
+    }
+
+    /// Sets pixel colors at the given drawing window, clipping to the panel.
+    pub fn set_pixels<P: IntoIterator<Item = u16>>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: P) {
+        if sx > ex || sy > ey || sx >= self.width || sy >= self.height {
+            return;
+        }
+        let window_width = ex as usize - sx as usize + 1;
+        for (i, color) in colors.into_iter().enumerate() {
+            let x = sx as usize + i % window_width;
+            let y = sy as usize + i / window_width;
+            if y > ey as usize {
+                break;
+            }
+            if x < self.width as usize && y < self.height as usize {
+                self.buffer[y * self.width as usize + x] = color;
+            }
+        }
+        self.mark_dirty(sx, sy, ex.min(self.width - 1), ey.min(self.height - 1));
+    }
+
+    /// Sends the pixels changed since the last flush to the panel.
+    pub fn flush(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let (sx, sy, ex, ey) = match self.dirty {
+            Some(dirty) => dirty,
+            None => return Ok(()),
+        };
+        self.display.set_address_window(sx, sy, ex, ey)?;
+        self.display.write_command(Instruction::RAMWR, None)?;
+        let stride = self.width as usize;
+        for y in sy as usize..=ey as usize {
+            let row = &self.buffer[y * stride + sx as usize..=y * stride + ex as usize];
+            self.display.write_pixel_slice(row)?;
+        }
+        self.display.finish_pixels()?;
+        self.dirty = None;
+        Ok(())
+    }
+
+    /// Sends the whole buffer to the panel, e.g. after it was drawn over
+    /// directly.
+    pub fn flush_all(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        self.mark_all_dirty();
+        self.flush()
+    }
+
+    fn mark_dirty(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) {
+        self.dirty = Some(match self.dirty {
+            Some((dsx, dsy, dex, dey)) => (dsx.min(sx), dsy.min(sy), dex.max(ex), dey.max(ey)),
+            None => (sx, sy, ex, ey),
+        });
+    }
+
+    fn mark_all_dirty(&mut self) {
+        if self.width > 0 && self.height > 0 {
+            self.mark_dirty(0, 0, self.width - 1, self.height - 1);
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+use crate::{area_window, point_coords};
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> OriginDimensions for FrameBuffer<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        let (width, height) = FrameBuffer::size(self);
+        Size::new(width as u32, height as u32)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> DrawTarget for FrameBuffer<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some((x, y)) = point_coords(point) {
+                self.set_pixel(x, y, color.into_storage());
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        match area_window(area) {
+            Some((sx, sy, ex, ey)) => {
+                let count = area.size.width as usize * area.size.height as usize;
+                let colors = colors.into_iter().take(count).map(|color| color.into_storage());
+                self.set_pixels(sx, sy, ex, ey, colors);
+                Ok(())
+            }
+            None => self.draw_iter(area.points().zip(colors).map(|(point, color)| Pixel(point, color))),
+        }
+    }
+}
+
+#[cfg(feature = "graphics-legacy")]
+mod legacy {
+    use super::*;
+
+    use embedded_graphics_legacy::{drawable::{Pixel, Dimensions}, pixelcolor::Rgb565, Drawing, SizedDrawing};
+
+    impl<'a, SPI, DC, RST> Drawing<Rgb565> for FrameBuffer<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(coord, color) in item_pixels {
+                if coord.0 <= u16::MAX as u32 && coord.1 <= u16::MAX as u32 {
+                    self.set_pixel(coord.0 as u16, coord.1 as u16, color.0);
+                }
+            }
+        }
+    }
+
+    impl<'a, SPI, DC, RST> SizedDrawing<Rgb565> for FrameBuffer<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw_sized<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>> + Dimensions,
+        {
+            let top_left = item_pixels.top_left();
+            let bottom_right = item_pixels.bottom_right();
+            // Items reaching past the top or left edge, or out of `u16`
+            // range, are clipped pixel by pixel instead of truncating the
+            // window coords.
+            let max = u16::MAX as i32;
+            if top_left.0 < 0 || top_left.1 < 0 || bottom_right.0 > max || bottom_right.1 > max {
+                return self.draw(item_pixels);
+            }
+
+            self.set_pixels(top_left.0 as u16, top_left.1 as u16,
+                            bottom_right.0 as u16, bottom_right.1 as u16,
+                            item_pixels.into_iter().map(|Pixel(_coord, color)| color.0));
+        }
+    }
+}
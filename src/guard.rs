@@ -0,0 +1,101 @@
+//! Putting the panel to sleep when the driver goes away.
+
+use crate::instruction::Instruction;
+use crate::ST7735;
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A driver that turns the panel off when dropped.
+///
+/// On drop the display is switched off, the controller is put into sleep
+/// mode and the backlight pin is driven low, so error paths that tear the
+/// driver down do not leave the panel lit. Errors at that point are
+/// ignored. All driver methods stay reachable through `Deref`.
+pub struct SleepOnDrop<SPI, DC, RST, BL>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    BL: OutputPin,
+{
+    /// Always `Some` until taken by `into_inner`
+    inner: Option<(ST7735<SPI, DC, RST>, BL)>,
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Wraps the driver so that dropping it puts the panel to sleep and
+    /// turns off the active-high `backlight`.
+    pub fn sleep_on_drop<BL: OutputPin>(self, backlight: BL) -> SleepOnDrop<SPI, DC, RST, BL> {
+        SleepOnDrop {
+            inner: Some((self, backlight)),
+        }
+    }
+}
+
+impl<SPI, DC, RST, BL> SleepOnDrop<SPI, DC, RST, BL>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    BL: OutputPin,
+{
+    /// Returns the driver and backlight pin without touching the panel.
+    pub fn into_inner(mut self) -> (ST7735<SPI, DC, RST>, BL) {
+        self.inner.take().unwrap()
+    }
+
+    /// Returns the backlight pin.
+    pub fn backlight(&mut self) -> &mut BL {
+        &mut self.inner.as_mut().unwrap().1
+    }
+}
+
+impl<SPI, DC, RST, BL> Drop for SleepOnDrop<SPI, DC, RST, BL>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    BL: OutputPin,
+{
+    fn drop(&mut self) {
+        if let Some((display, backlight)) = self.inner.as_mut() {
+            let _ = backlight.set_low();
+            let _ = display.write_command(Instruction::DISPOFF, None);
+            let _ = display.write_command(Instruction::SLPIN, None);
+        }
+    }
+}
+
+impl<SPI, DC, RST, BL> Deref for SleepOnDrop<SPI, DC, RST, BL>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    BL: OutputPin,
+{
+    type Target = ST7735<SPI, DC, RST>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.as_ref().unwrap().0
+    }
+}
+
+impl<SPI, DC, RST, BL> DerefMut for SleepOnDrop<SPI, DC, RST, BL>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    BL: OutputPin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner.as_mut().unwrap().0
+    }
+}
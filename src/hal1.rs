@@ -0,0 +1,115 @@
+//! Using the driver with embedded-hal 1.0 peripherals.
+//!
+//! The driver is written against the embedded-hal 0.2 traits. The wrappers
+//! here implement those on top of 1.0 peripherals, so a 1.0 HAL can be
+//! used as is:
+//!
+//! ```ignore
+//! let mut display = ST7735::new_hal1(spi_device, dc, rst, true, false, 160, 128);
+//! display.init(&mut DelayCompat(delay))?;
+//! ```
+//!
+//! With [`SpiDeviceCompat`] every command and every data write is its own
+//! transaction, so chip select is handled by the device and the bus can be
+//! shared. The controller only answers reads while chip select stays low
+//! after the command, so reading back needs exclusive use of the bus
+//! through [`SpiBusCompat`].
+
+use crate::ST7735;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use embedded_hal_1::delay::DelayNs;
+use embedded_hal_1::digital::OutputPin as OutputPin1;
+use embedded_hal_1::spi::{SpiBus, SpiDevice};
+
+/// An embedded-hal 1.0 SPI device, managing chip select by itself.
+pub struct SpiDeviceCompat<D>(pub D);
+
+impl<D: SpiDevice> spi::Write<u8> for SpiDeviceCompat<D> {
+    type Error = D::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), D::Error> {
+        self.0.write(words)
+    }
+}
+
+/// An embedded-hal 1.0 SPI bus used exclusively by the display, with chip
+/// select tied low or driven by the application.
+pub struct SpiBusCompat<B>(pub B);
+
+impl<B: SpiBus> spi::Write<u8> for SpiBusCompat<B> {
+    type Error = B::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), B::Error> {
+        self.0.write(words)?;
+        // DC must not change before the last bit is out.
+        self.0.flush()
+    }
+}
+
+impl<B: SpiBus> spi::Transfer<u8> for SpiBusCompat<B> {
+    type Error = B::Error;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], B::Error> {
+        self.0.transfer_in_place(words)?;
+        self.0.flush()?;
+        Ok(words)
+    }
+}
+
+/// An embedded-hal 1.0 output pin.
+pub struct OutputPinCompat<P>(pub P);
+
+impl<P: OutputPin1> OutputPin for OutputPinCompat<P> {
+    type Error = P::Error;
+
+    fn set_low(&mut self) -> Result<(), P::Error> {
+        self.0.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), P::Error> {
+        self.0.set_high()
+    }
+}
+
+/// An embedded-hal 1.0 delay, e.g. for [`ST7735::init`].
+pub struct DelayCompat<D>(pub D);
+
+impl<D: DelayNs> DelayMs<u8> for DelayCompat<D> {
+    fn delay_ms(&mut self, ms: u8) {
+        self.0.delay_ms(ms as u32);
+    }
+}
+
+impl<D, DC, RST> ST7735<SpiDeviceCompat<D>, OutputPinCompat<DC>, OutputPinCompat<RST>>
+where
+    D: SpiDevice,
+    DC: OutputPin1,
+    RST: OutputPin1,
+{
+    /// Creates a new driver instance on an embedded-hal 1.0 SPI device.
+    ///
+    /// The arguments are those of [`ST7735::new`].
+    pub fn new_hal1(
+        spi: D,
+        dc: DC,
+        rst: RST,
+        rgb: bool,
+        inverted: bool,
+        width: u16,
+        height: u16,
+    ) -> Self {
+        ST7735::new(
+            SpiDeviceCompat(spi),
+            OutputPinCompat(dc),
+            OutputPinCompat(rst),
+            rgb,
+            inverted,
+            width,
+            height,
+        )
+    }
+}
@@ -0,0 +1,285 @@
+//! Hybrid drawing: scattered pixels are buffered, large writes go direct.
+
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Window area, in pixels, from which writes bypass the buffer by default.
+pub const DEFAULT_DIRECT_THRESHOLD: usize = 64;
+
+/// A drawing target that picks the cheaper path for every write.
+///
+/// Small writes land in a caller-provided frame buffer and are sent in one
+/// go by [`Hybrid::flush`], covering the bounding box of everything changed
+/// since the last flush. Writes covering at least the configured threshold
+/// are streamed straight to the panel and mirrored into the buffer, so the
+/// buffer always holds what the panel will show after the next flush.
+pub struct Hybrid<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    buffer: &'a mut [u16],
+    width: u16,
+    height: u16,
+    threshold: usize,
+    /// Bounding box `(sx, sy, ex, ey)` of pixels not yet sent
+    dirty: Option<(u16, u16, u16, u16)>,
+}
+
+impl<'a, SPI, DC, RST> Hybrid<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a hybrid target for a `width` x `height` panel.
+    ///
+    /// `buffer` must hold at least `width * height` pixels and should match
+    /// what is on the panel, e.g. by clearing both first.
+    pub fn new(display: &'a mut ST7735<SPI, DC, RST>, buffer: &'a mut [u16], width: u16, height: u16) -> Self {
+        assert!(buffer.len() >= width as usize * height as usize);
+        Hybrid {
+            display,
+            buffer,
+            width,
+            height,
+            threshold: DEFAULT_DIRECT_THRESHOLD,
+            dirty: None,
+        }
+    }
+
+    /// Sets the window area from which writes go straight to the panel.
+    pub fn set_direct_threshold(&mut self, pixels: usize) {
+        self.threshold = pixels;
+    }
+
+    /// Returns the frame buffer contents.
+    pub fn buffer(&self) -> &[u16] {
+        self.buffer
+    }
+
+    /// Returns the panel size the buffer covers.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Returns the underlying driver for drawing past the buffer.
+    pub(crate) fn display(&mut self) -> &mut ST7735<SPI, DC, RST> {
+        self.display
+    }
+
+    /// Returns whether there are buffered pixels waiting for a flush.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Sets a pixel color in the buffer, clipping to the panel.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        self.buffer[y as usize * self.width as usize + x as usize] = color;
+        self.mark_dirty(x, y, x, y);
+    }
+
+    /// Sets pixel colors at the given drawing window, clipping to the panel.
+    ///
+    /// Windows of at least the direct threshold are sent right away.
+    pub fn set_pixels<P: IntoIterator<Item = u16>>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: P,
+    ) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey || sx >= self.width || sy >= self.height {
+            return Ok(());
+        }
+        let cex = ex.min(self.width - 1);
+        let cey = ey.min(self.height - 1);
+        let window_width = ex as usize - sx as usize + 1;
+        let stride = self.width as usize;
+        let buffer = &mut *self.buffer;
+        let visible = colors.into_iter().enumerate().filter_map(move |(i, color)| {
+            let x = sx as usize + i % window_width;
+            let y = sy as usize + i / window_width;
+            if x <= cex as usize && y <= cey as usize {
+                buffer[y * stride + x] = color;
+                Some(color)
+            } else {
+                None
+            }
+        });
+
+        let area = (cex as usize - sx as usize + 1) * (cey as usize - sy as usize + 1);
+        if area >= self.threshold {
+            self.display.set_pixels(sx, sy, cex, cey, visible)
+        } else {
+            visible.for_each(drop);
+            self.mark_dirty(sx, sy, cex, cey);
+            Ok(())
+        }
+    }
+
+    /// Sends the buffered changes to the panel.
+    pub fn flush(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let (sx, sy, ex, ey) = match self.dirty {
+            Some(dirty) => dirty,
+            None => return Ok(()),
+        };
+        self.send(sx, sy, ex, ey)?;
+        self.dirty = None;
+        Ok(())
+    }
+
+    /// Resends the buffered contents of `region`, clipped to the panel,
+    /// e.g. to repair pixels drawn past the buffer.
+    pub fn refresh(&mut self, region: Region) -> Result<(), DisplayError<SPI, DC>> {
+        if region.width == 0 || region.height == 0 || region.x >= self.width || region.y >= self.height {
+            return Ok(());
+        }
+        let ex = (region.x as u32 + region.width as u32 - 1).min(self.width as u32 - 1) as u16;
+        let ey = (region.y as u32 + region.height as u32 - 1).min(self.height as u32 - 1) as u16;
+        self.send(region.x, region.y, ex, ey)
+    }
+
+    fn send(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.display.set_address_window(sx, sy, ex, ey)?;
+        self.display.write_command(Instruction::RAMWR, None)?;
+        let stride = self.width as usize;
+        for y in sy as usize..=ey as usize {
+            let row = &self.buffer[y * stride + sx as usize..=y * stride + ex as usize];
+            self.display.write_pixel_slice(row)?;
+        }
+        self.display.finish_pixels()
+    }
+
+    fn mark_dirty(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) {
+        self.dirty = Some(match self.dirty {
+            Some((dsx, dsy, dex, dey)) => (dsx.min(sx), dsy.min(sy), dex.max(ex), dey.max(ey)),
+            None => (sx, sy, ex, ey),
+        });
+    }
+}
+
+#[cfg(feature = "graphics")]
+use crate::{area_window, point_coords};
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> OriginDimensions for Hybrid<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        let (width, height) = Hybrid::size(self);
+        Size::new(width as u32, height as u32)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> DrawTarget for Hybrid<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = DisplayError<SPI, DC>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some((x, y)) = point_coords(point) {
+                self.set_pixel(x, y, color.into_storage());
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        match area_window(area) {
+            Some((sx, sy, ex, ey)) => {
+                let count = area.size.width as usize * area.size.height as usize;
+                let colors = colors.into_iter().take(count).map(|color| color.into_storage());
+                self.set_pixels(sx, sy, ex, ey, colors)
+            }
+            None => self.draw_iter(area.points().zip(colors).map(|(point, color)| Pixel(point, color))),
+        }
+    }
+}
+
+#[cfg(feature = "graphics-legacy")]
+mod legacy {
+    use super::*;
+
+    use embedded_graphics_legacy::{drawable::{Pixel, Dimensions}, pixelcolor::Rgb565, Drawing, SizedDrawing};
+
+    impl<'a, SPI, DC, RST> Drawing<Rgb565> for Hybrid<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(coord, color) in item_pixels {
+                if coord.0 <= u16::MAX as u32 && coord.1 <= u16::MAX as u32 {
+                    self.set_pixel(coord.0 as u16, coord.1 as u16, color.0);
+                }
+            }
+        }
+    }
+
+    impl<'a, SPI, DC, RST> SizedDrawing<Rgb565> for Hybrid<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw_sized<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>> + Dimensions,
+        {
+            let top_left = item_pixels.top_left();
+            let bottom_right = item_pixels.bottom_right();
+            // Items reaching past the top or left edge, or out of `u16`
+            // range, are clipped pixel by pixel instead of truncating the
+            // window coords.
+            let max = u16::MAX as i32;
+            if top_left.0 < 0 || top_left.1 < 0 || bottom_right.0 > max || bottom_right.1 > max {
+                return self.draw(item_pixels);
+            }
+
+            let result = self.set_pixels(top_left.0 as u16, top_left.1 as u16,
+                            bottom_right.0 as u16, bottom_right.1 as u16,
+                            item_pixels.into_iter().map(|Pixel(_coord, color)| color.0));
+            if result.is_err() {
+                panic!("pixels write failed");
+            }
+        }
+    }
+}
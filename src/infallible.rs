@@ -0,0 +1,129 @@
+//! A `Result`-free API for HALs whose SPI and pins cannot fail.
+
+use crate::{Error, InitCommand, Orientation, ST7735};
+
+use core::convert::Infallible;
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A driver whose bus and pins are statically known not to fail.
+///
+/// The drawing methods return nothing instead of a `Result`, saving the
+/// `unwrap()` on every call. All other driver methods stay reachable
+/// through `Deref`.
+///
+/// The errors left are not caused by the bus, e.g. drawing out of bounds
+/// with [`BoundsPolicy::Error`](crate::BoundsPolicy::Error). They panic
+/// here with a message naming the error.
+pub struct InfallibleDisplay<SPI, DC, RST>
+where
+    SPI: spi::Write<u8, Error = Infallible>,
+    DC: OutputPin<Error = Infallible>,
+    RST: OutputPin<Error = Infallible>,
+{
+    display: ST7735<SPI, DC, RST>,
+}
+
+/// Turns the errors an infallible bus leaves into a panic naming them.
+fn unwrap_infallible(result: Result<(), Error<Infallible, Infallible>>) {
+    match result {
+        Ok(()) => {}
+        Err(Error::Spi(e)) | Err(Error::Dc(e)) | Err(Error::Rst(e)) | Err(Error::Te(e)) => match e {},
+        Err(Error::OutOfBounds) => panic!("drawing out of bounds"),
+        Err(Error::InvalidArgument) => panic!("invalid argument"),
+        Err(Error::Busy) => panic!("display busy"),
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8, Error = Infallible>,
+    DC: OutputPin<Error = Infallible>,
+    RST: OutputPin<Error = Infallible>,
+{
+    /// Wraps the driver in the `Result`-free API.
+    pub fn into_infallible(self) -> InfallibleDisplay<SPI, DC, RST> {
+        InfallibleDisplay { display: self }
+    }
+}
+
+impl<SPI, DC, RST> InfallibleDisplay<SPI, DC, RST>
+where
+    SPI: spi::Write<u8, Error = Infallible>,
+    DC: OutputPin<Error = Infallible>,
+    RST: OutputPin<Error = Infallible>,
+{
+    /// Returns the wrapped driver.
+    pub fn into_inner(self) -> ST7735<SPI, DC, RST> {
+        self.display
+    }
+
+    /// Runs commands to initialize the display.
+    pub fn init<DELAY>(&mut self, delay: &mut DELAY)
+        where DELAY: DelayMs<u8>
+    {
+        unwrap_infallible(self.display.init(delay))
+    }
+
+    /// Initializes the display, then sends `commands`.
+    pub fn init_with<DELAY>(&mut self, delay: &mut DELAY, commands: &[InitCommand])
+        where DELAY: DelayMs<u8>
+    {
+        unwrap_infallible(self.display.init_with(delay, commands))
+    }
+
+    pub fn hard_reset(&mut self) {
+        unwrap_infallible(self.display.hard_reset())
+    }
+
+    pub fn set_orientation(&mut self, orientation: &Orientation) {
+        unwrap_infallible(self.display.set_orientation(orientation))
+    }
+
+    /// Sets a pixel color at the given coords.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+        unwrap_infallible(self.display.set_pixel(x, y, color))
+    }
+
+    /// Writes pixel colors sequentially into the current drawing window
+    pub fn write_pixels<P: IntoIterator<Item = u16>>(&mut self, colors: P) {
+        unwrap_infallible(self.display.write_pixels(colors))
+    }
+
+    /// Writes pre-encoded pixel bytes sequentially into the current drawing window
+    pub fn write_raw_iter<B: IntoIterator<Item = u8>>(&mut self, bytes: B) {
+        unwrap_infallible(self.display.write_raw_iter(bytes))
+    }
+
+    /// Sets pixel colors at the given drawing window
+    pub fn set_pixels<P: IntoIterator<Item = u16>>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: P) {
+        unwrap_infallible(self.display.set_pixels(sx, sy, ex, ey, colors))
+    }
+}
+
+impl<SPI, DC, RST> Deref for InfallibleDisplay<SPI, DC, RST>
+where
+    SPI: spi::Write<u8, Error = Infallible>,
+    DC: OutputPin<Error = Infallible>,
+    RST: OutputPin<Error = Infallible>,
+{
+    type Target = ST7735<SPI, DC, RST>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.display
+    }
+}
+
+impl<SPI, DC, RST> DerefMut for InfallibleDisplay<SPI, DC, RST>
+where
+    SPI: spi::Write<u8, Error = Infallible>,
+    DC: OutputPin<Error = Infallible>,
+    RST: OutputPin<Error = Infallible>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.display
+    }
+}
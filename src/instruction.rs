@@ -1,12 +1,18 @@
 use num_derive::ToPrimitive;
 
 /// ST7735 instructions.
-#[derive(ToPrimitive)]
+#[derive(ToPrimitive, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Instruction {
     NOP = 0x00,
     SWRESET = 0x01,
     RDDID = 0x04,
     RDDST = 0x09,
+    RDDPM = 0x0A,
+    RDDMADCTL = 0x0B,
+    RDDCOLMOD = 0x0C,
+    RDDIM = 0x0D,
+    RDDSM = 0x0E,
     SLPIN = 0x10,
     SLPOUT = 0x11,
     PTLON = 0x12,
@@ -20,8 +26,14 @@ pub enum Instruction {
     RAMWR = 0x2C,
     RAMRD = 0x2E,
     PTLAR = 0x30,
+    VSCRDEF = 0x33,
+    TEOFF = 0x34,
+    TEON = 0x35,
     COLMOD = 0x3A,
     MADCTL = 0x36,
+    VSCSAD = 0x37,
+    IDMOFF = 0x38,
+    IDMON = 0x39,
     FRMCTR1 = 0xB1,
     FRMCTR2 = 0xB2,
     FRMCTR3 = 0xB3,
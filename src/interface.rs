@@ -0,0 +1,130 @@
+//! Using the driver with a `display-interface` transport.
+//!
+//! This is an adapter, not a port: the driver still talks to the panel
+//! through an SPI bus and a DC pin. The halves here implement those on
+//! top of any [`WriteOnlyDataCommand`], so the drawing code runs
+//! unchanged on `display-interface-spi`, parallel interfaces or custom
+//! bus wrappers:
+//!
+//! ```ignore
+//! let interface = RefCell::new(Interface::new(di));
+//! let mut display = ST7735::new_interface(&interface, NoResetPin::new(), true, false, 128, 160);
+//! display.init(&mut delay)?;
+//! ```
+//!
+//! Bytes written while DC is low go out through
+//! [`WriteOnlyDataCommand::send_commands`], all others through
+//! [`WriteOnlyDataCommand::send_data`]. Transport errors are reported as
+//! [`Error::Spi`](crate::Error::Spi). The DC half cannot fail; its error
+//! type follows that of the reset pin, so any reset pin can be used. The
+//! interface is write-only, so nothing can be read back through it.
+
+use crate::ST7735;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use display_interface::{DataFormat, DisplayError as InterfaceError, WriteOnlyDataCommand};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A `display-interface` transport shared by [`InterfaceSpi`] and
+/// [`InterfaceDc`].
+pub struct Interface<DI> {
+    di: DI,
+    dc: bool,
+}
+
+impl<DI: WriteOnlyDataCommand> Interface<DI> {
+    /// Wraps a transport, starting in command mode.
+    pub fn new(di: DI) -> Self {
+        Interface { di, dc: false }
+    }
+
+    /// Returns the transport.
+    pub fn release(self) -> DI {
+        self.di
+    }
+}
+
+/// Bus half of an [`Interface`].
+pub struct InterfaceSpi<'a, DI>(pub &'a RefCell<Interface<DI>>);
+
+impl<'a, DI: WriteOnlyDataCommand> spi::Write<u8> for InterfaceSpi<'a, DI> {
+    type Error = InterfaceError;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), InterfaceError> {
+        let mut interface = self.0.borrow_mut();
+        if interface.dc {
+            interface.di.send_data(DataFormat::U8(words))
+        } else {
+            interface.di.send_commands(DataFormat::U8(words))
+        }
+    }
+}
+
+/// Data/command half of an [`Interface`], switching which of the
+/// transport's methods bus writes go to.
+///
+/// Setting it never fails. The error type `E` only matches it up with the
+/// reset pin, as [`ST7735::init`] requires.
+pub struct InterfaceDc<'a, DI, E = Infallible> {
+    interface: &'a RefCell<Interface<DI>>,
+    error: PhantomData<E>,
+}
+
+impl<'a, DI, E> InterfaceDc<'a, DI, E> {
+    /// Creates the DC half of `interface`.
+    pub fn new(interface: &'a RefCell<Interface<DI>>) -> Self {
+        InterfaceDc {
+            interface,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'a, DI, E> OutputPin for InterfaceDc<'a, DI, E> {
+    type Error = E;
+
+    fn set_low(&mut self) -> Result<(), E> {
+        self.interface.borrow_mut().dc = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), E> {
+        self.interface.borrow_mut().dc = true;
+        Ok(())
+    }
+}
+
+impl<'a, DI, RST> ST7735<InterfaceSpi<'a, DI>, InterfaceDc<'a, DI, RST::Error>, RST>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin,
+{
+    /// Creates a new driver instance on a `display-interface` transport.
+    ///
+    /// Transports usually leave the reset line to the application; pass
+    /// [`NoResetPin`](crate::NoResetPin) in that case. The other arguments
+    /// are those of [`ST7735::new`].
+    pub fn new_interface(
+        interface: &'a RefCell<Interface<DI>>,
+        rst: RST,
+        rgb: bool,
+        inverted: bool,
+        width: u16,
+        height: u16,
+    ) -> Self {
+        ST7735::new(
+            InterfaceSpi(interface),
+            InterfaceDc::new(interface),
+            rst,
+            rgb,
+            inverted,
+            width,
+            height,
+        )
+    }
+}
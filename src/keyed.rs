@@ -0,0 +1,89 @@
+//! Drawing raw RGB565 images with a transparent key color.
+
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::{DisplayError, Error, CHUNK_PIXELS, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws a raw RGB565 image at `rect`, leaving pixels of color `key`
+    /// untouched.
+    ///
+    /// `data` holds two little-endian bytes per pixel, row-major, as in
+    /// `embedded-graphics`' `Image16BPP` assets, and is read in place, so
+    /// it can stay in flash. Each row is split into runs of visible
+    /// pixels; every run gets its own window and keyed pixels are skipped
+    /// without being sent. The image is clipped to the panel.
+    ///
+    /// Fails without drawing anything unless `data` holds exactly
+    /// `rect.width * rect.height` pixels.
+    pub fn draw_keyed(&mut self, rect: Region, data: &[u8], key: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.draw_raw(rect, data, Some(key))
+    }
+
+    /// Copies a raw RGB565 sprite of `width` x `height` pixels to `(x, y)`,
+    /// skipping pixels of the `transparent` color if one is given.
+    ///
+    /// `data` is laid out as for [`ST7735::draw_keyed`] and the sprite is
+    /// clipped to the panel the same way.
+    pub fn blit(&mut self, x: u16, y: u16, width: u16, height: u16, data: &[u8], transparent: Option<u16>) -> Result<(), DisplayError<SPI, DC>> {
+        self.draw_raw(Region::new(x, y, width, height), data, transparent)
+    }
+
+    /// Draws a raw little-endian RGB565 image, skipping pixels of color
+    /// `key` if given.
+    fn draw_raw(&mut self, rect: Region, data: &[u8], key: Option<u16>) -> Result<(), DisplayError<SPI, DC>> {
+        let row_bytes = rect.width as usize * 2;
+        if data.len() != row_bytes * rect.height as usize {
+            return Err(Error::InvalidArgument);
+        }
+        if rect.x >= self.width || rect.y >= self.height {
+            return Ok(());
+        }
+        let visible_width = rect.width.min(self.width - rect.x) as usize;
+        let visible_rows = rect.height.min(self.height - rect.y);
+        for (y, row) in (rect.y..rect.y + visible_rows).zip(data.chunks(row_bytes.max(1))) {
+            let row = &row[..visible_width * 2];
+            let mut x = 0;
+            while x < visible_width {
+                if Some(pixel(row, x)) == key {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < visible_width && Some(pixel(row, x)) != key {
+                    x += 1;
+                }
+                let sx = rect.x + start as u16;
+                self.set_address_window(sx, y, rect.x + x as u16 - 1, y)?;
+                self.write_command(Instruction::RAMWR, None)?;
+                self.write_keyed_run(&row[start * 2..x * 2])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Byte swaps, converts and sends a run of little-endian pixels.
+    fn write_keyed_run(&mut self, run: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        let mut converted = [0u16; CHUNK_PIXELS];
+        for chunk in run.chunks(converted.len() * 2) {
+            for (out, pair) in converted.iter_mut().zip(chunk.chunks_exact(2)) {
+                *out = self.convert_color(u16::from_le_bytes([pair[0], pair[1]]));
+            }
+            self.write_encoded(&converted[..chunk.len() / 2])?;
+        }
+        self.finish_pixels()
+    }
+}
+
+/// Returns pixel `x` of a row of little-endian pixels.
+fn pixel(row: &[u8], x: usize) -> u16 {
+    u16::from_le_bytes([row[2 * x], row[2 * x + 1]])
+}
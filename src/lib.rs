@@ -2,18 +2,93 @@
 
 //! This crate provides a ST7735 driver to connect to TFT displays.
 
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod backlight;
+pub mod bdf;
+pub mod budget;
+#[cfg(feature = "calibration")]
+pub mod calibration;
+#[cfg(feature = "canvas")]
+pub mod canvas;
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod color;
+pub mod console;
+pub mod cs;
+pub mod double;
+pub mod encode;
+pub mod flush;
+pub mod font;
+#[cfg(feature = "buffered")]
+pub mod framebuffer;
+pub mod guard;
+#[cfg(feature = "hal1")]
+pub mod hal1;
+pub mod hybrid;
+pub mod infallible;
 pub mod instruction;
+#[cfg(feature = "interface")]
+pub mod interface;
+mod keyed;
+pub mod list;
+#[cfg(feature = "log")]
+pub mod logger;
+pub mod marquee;
+pub mod menu;
+pub mod model;
+#[cfg(feature = "panic")]
+pub mod panic;
+pub mod parallel;
+mod power;
+#[cfg(feature = "primitives")]
+pub mod primitives;
+pub mod progress;
+pub mod queue;
+pub mod ramp;
+#[cfg(feature = "readback")]
+mod read;
+pub mod region;
+pub mod scanline;
+pub mod scope;
+pub mod scroll;
+#[cfg(feature = "readback")]
+pub mod selfcheck;
+pub mod sequence;
+#[cfg(feature = "critical-section")]
+pub mod shared;
+pub mod spectrum;
+#[cfg(feature = "sprites")]
+pub mod sprites;
+pub mod strip;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod text;
+pub mod toast;
+pub mod tuning;
+mod unchecked;
+pub mod variant;
 
-use core::mem::transmute;
-
+use crate::color::{ColorTransform, HighContrast, NightMode};
 use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::variant::Variant;
 use num_traits::ToPrimitive;
 use num_derive::ToPrimitive;
 
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
 use embedded_hal::digital::v2::OutputPin;
 use embedded_hal::blocking::spi;
 use embedded_hal::blocking::delay::DelayMs;
 
+/// Pixels converted and encoded per bus write.
+///
+/// Writing a chunk at a time keeps the per-write overhead of the bus off
+/// every single pixel while needing only a small buffer on the stack.
+pub(crate) const CHUNK_PIXELS: usize = 32;
+
 /// ST7735 driver to connect to TFT displays.
 pub struct ST7735 <SPI, DC, RST>
 where
@@ -39,10 +114,58 @@ where
     /// Global image offset
     dx: u16,
     dy: u16,
+
+    /// Panel size in pixels
+    width: u16,
+    height: u16,
+
+    /// How drawing outside the panel is handled
+    bounds: BoundsPolicy,
+
+    /// Number of commands sent so far, used to detect interrupted RAM writes
+    command_seq: u32,
+
+    /// Last value written to MADCTL
+    madctl: u8,
+
+    /// User transform applied to every pixel, if registered
+    transform: Option<ColorTransform>,
+
+    /// High-contrast scheme applied to every pixel, if enabled
+    high_contrast: Option<HighContrast>,
+
+    /// Night mode applied to every pixel, if enabled
+    night_mode: Option<NightMode>,
+
+    /// How failed bus writes are retried
+    retry: RetryPolicy,
+
+    /// Whether idle mode is on
+    idle: bool,
+
+    /// Pixel format selected with COLMOD
+    format: PixelFormat,
+
+    /// RGB444 pixel waiting for the second half of its byte triple
+    pending: Option<u16>,
+
+    /// Orientation last set, which `width` and `height` follow
+    orientation: Orientation,
+
+    /// MADCTL bits toggled on top of the orientation
+    flags: MadctlFlags,
+
+    /// Image offset of each orientation, loaded into `dx` and `dy` when it
+    /// is set
+    offsets: [(u16, u16); 4],
+
+    /// Panel variant, if created for one
+    variant: Option<Variant>,
 }
 
 /// Display orientation.
-#[derive(ToPrimitive)]
+#[derive(ToPrimitive, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Orientation {
     Portrait = 0x00,
     Landscape = 0x60,
@@ -50,6 +173,249 @@ pub enum Orientation {
     LandscapeSwapped = 0xA0,
 }
 
+impl Orientation {
+    /// Returns whether rows and columns are exchanged with respect to
+    /// portrait.
+    pub fn is_landscape(&self) -> bool {
+        matches!(self, Orientation::Landscape | Orientation::LandscapeSwapped)
+    }
+
+    /// Returns the position of the orientation in per-orientation tables.
+    fn index(&self) -> usize {
+        match self {
+            Orientation::Portrait => 0,
+            Orientation::Landscape => 1,
+            Orientation::PortraitSwapped => 2,
+            Orientation::LandscapeSwapped => 3,
+        }
+    }
+}
+
+/// Individual MADCTL bits, toggled on top of the [`Orientation`].
+///
+/// Combine them with `|`, e.g. to correct a panel seen through a mirror.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MadctlFlags(u8);
+
+impl MadctlFlags {
+    /// No bits toggled.
+    pub const NONE: MadctlFlags = MadctlFlags(0x00);
+
+    /// Row address order, mirroring vertically.
+    pub const MY: MadctlFlags = MadctlFlags(0x80);
+
+    /// Column address order, mirroring horizontally.
+    pub const MX: MadctlFlags = MadctlFlags(0x40);
+
+    /// Row and column exchange, swapping width and height.
+    pub const MV: MadctlFlags = MadctlFlags(0x20);
+
+    /// Vertical refresh order.
+    pub const ML: MadctlFlags = MadctlFlags(0x10);
+
+    /// Horizontal refresh order.
+    pub const MH: MadctlFlags = MadctlFlags(0x04);
+
+    /// Returns the raw MADCTL bits.
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns whether all bits of `other` are set.
+    pub const fn contains(&self, other: MadctlFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for MadctlFlags {
+    type Output = MadctlFlags;
+
+    fn bitor(self, rhs: MadctlFlags) -> MadctlFlags {
+        MadctlFlags(self.0 | rhs.0)
+    }
+}
+
+/// Pixel format on the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PixelFormat {
+    /// 12 bits per pixel, two pixels in three bytes.
+    Rgb444,
+
+    /// 16 bits per pixel, the driver's default.
+    Rgb565,
+
+    /// 18 bits per pixel, sent as three bytes.
+    Rgb666,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes sent for `pixels` pixels.
+    pub const fn bytes(&self, pixels: u64) -> u64 {
+        match self {
+            PixelFormat::Rgb444 => (pixels * 3).div_ceil(2),
+            PixelFormat::Rgb565 => pixels * 2,
+            PixelFormat::Rgb666 => pixels * 3,
+        }
+    }
+
+    /// Returns the `COLMOD` parameter selecting the format.
+    pub const fn colmod(&self) -> u8 {
+        match self {
+            PixelFormat::Rgb444 => 0x03,
+            PixelFormat::Rgb565 => 0x05,
+            PixelFormat::Rgb666 => 0x06,
+        }
+    }
+}
+
+/// Signal on the controller's tearing effect (TE) output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TearingEffect {
+    /// No signal.
+    Off,
+
+    /// High during vertical blanking.
+    Vertical,
+
+    /// High during vertical and horizontal blanking.
+    HorizontalAndVertical,
+}
+
+/// How failed SPI writes of commands and data are retried.
+///
+/// A write that fails part way may already have sent some of its bytes,
+/// so retrying pixel data can shift the rest of a frame. Transient errors
+/// are usually reported before anything was sent, which is the case this
+/// is meant for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryPolicy {
+    /// Number of extra attempts after a failed write.
+    pub retries: u8,
+
+    /// Whether to drive the DC pin again before every retry, in case the
+    /// error left it in the wrong state.
+    pub resync: bool,
+}
+
+impl RetryPolicy {
+    /// Fails on the first error.
+    pub const NONE: RetryPolicy = RetryPolicy { retries: 0, resync: false };
+
+    /// Retries failed writes up to `retries` times.
+    pub const fn new(retries: u8, resync: bool) -> Self {
+        RetryPolicy { retries, resync }
+    }
+}
+
+/// Errors returned by the driver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<SpiE, PinE> {
+    /// The SPI bus reported an error.
+    Spi(SpiE),
+
+    /// Setting the data/command pin failed.
+    Dc(PinE),
+
+    /// Setting the reset pin failed.
+    Rst(PinE),
+
+    /// Reading the tearing effect pin failed.
+    Te(PinE),
+
+    /// Drawing reached outside the panel under [`BoundsPolicy::Error`], or
+    /// a window to read was not on the panel.
+    OutOfBounds,
+
+    /// An argument was invalid, e.g. a buffer of the wrong length.
+    InvalidArgument,
+
+    /// The driver was in use and the request could not be queued, or the
+    /// tearing effect signal did not arrive in time.
+    Busy,
+}
+
+/// Error type of a driver on bus `SPI` with data/command pin `DC`.
+pub type DisplayError<SPI, DC> = Error<<SPI as spi::Write<u8>>::Error, <DC as OutputPin>::Error>;
+
+/// Stand-in for the reset pin on boards that tie RST to the MCU reset
+/// line or to VCC.
+///
+/// Setting it does nothing, so [`ST7735::hard_reset`] is a no-op and
+/// [`ST7735::init`] relies on its `SWRESET`. The error type takes on that
+/// of the DC pin as `init` requires.
+pub struct NoResetPin<E = Infallible>(PhantomData<E>);
+
+impl<E> NoResetPin<E> {
+    /// Creates the stand-in pin.
+    pub const fn new() -> Self {
+        NoResetPin(PhantomData)
+    }
+}
+
+impl<E> Default for NoResetPin<E> {
+    fn default() -> Self {
+        NoResetPin::new()
+    }
+}
+
+impl<E> OutputPin for NoResetPin<E> {
+    type Error = E;
+
+    fn set_low(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// A command sent during initialization, by [`ST7735::init_with`] after
+/// the built-in sequence or as part of a [`Model`](model::Model)'s settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitCommand<'a> {
+    /// Command to send.
+    pub instr: Instruction,
+
+    /// Parameters sent after the command.
+    pub params: &'a [u8],
+
+    /// Time to wait afterwards in milliseconds.
+    pub delay_ms: u8,
+}
+
+impl<'a> InitCommand<'a> {
+    /// Sends `instr` with `params` and no delay.
+    pub const fn new(instr: Instruction, params: &'a [u8]) -> Self {
+        InitCommand { instr, params, delay_ms: 0 }
+    }
+
+    /// Waits `delay_ms` after sending the command.
+    pub const fn delay_ms(mut self, delay_ms: u8) -> Self {
+        self.delay_ms = delay_ms;
+        self
+    }
+}
+
+/// How drawing outside the panel is handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BoundsPolicy {
+    /// Silently drop pixels outside the panel.
+    Clip,
+
+    /// Fail the whole operation without drawing anything.
+    Error,
+
+    /// Wrap coordinates around the panel edges.
+    Wrap,
+}
+
 impl<SPI, DC, RST> ST7735<SPI, DC, RST>
 where
     SPI: spi::Write<u8>,
@@ -57,182 +423,910 @@ where
     RST: OutputPin,
 {
     /// Creates a new driver instance that uses hardware SPI.
+    ///
+    /// `width` and `height` are the size of the panel in portrait
+    /// orientation, and are swapped while in landscape.
     pub fn new(
         spi: SPI,
         dc: DC,
         rst: RST,
         rgb: bool,
         inverted: bool,
+        width: u16,
+        height: u16,
     ) -> Self
     {
-        let display = ST7735 {
+        ST7735 {
             spi,
             dc,
             rst,
             rgb,
             inverted,
             dx: 0,
-            dy: 0
-        };
+            dy: 0,
+            width,
+            height,
+            bounds: BoundsPolicy::Clip,
+            command_seq: 0,
+            madctl: 0,
+            transform: None,
+            high_contrast: None,
+            night_mode: None,
+            retry: RetryPolicy::NONE,
+            idle: false,
+            format: PixelFormat::Rgb565,
+            pending: None,
+            orientation: Orientation::Portrait,
+            flags: MadctlFlags::NONE,
+            offsets: [(0, 0); 4],
+            variant: None,
+        }
+    }
 
-        display
+    /// Destroys the driver, returning the bus and pins.
+    ///
+    /// The panel keeps its state; put it to sleep first if the bus is
+    /// going to be used for other devices.
+    pub fn release(self) -> (SPI, DC, RST) {
+        (self.spi, self.dc, self.rst)
     }
 
-    /// Runs commands to initialize the display.
-    pub fn init<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), ()>
-        where DELAY: DelayMs<u8>
-    {
-        self.hard_reset()?;
-        self.write_command(Instruction::SWRESET, None)?;
-        delay.delay_ms(200);
-        self.write_command(Instruction::SLPOUT, None)?;
-        delay.delay_ms(200);
-        self.write_command(Instruction::FRMCTR1, Some(&[0x01, 0x2C, 0x2D]))?;
-        self.write_command(Instruction::FRMCTR2, Some(&[0x01, 0x2C, 0x2D]))?;
-        self.write_command(Instruction::FRMCTR3,
-            Some(&[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D]))?;
-        self.write_command(Instruction::INVCTR, Some(&[0x07]))?;
-        self.write_command(Instruction::PWCTR1, Some(&[0xA2, 0x02, 0x84]))?;
-        self.write_command(Instruction::PWCTR2, Some(&[0xC5]))?;
-        self.write_command(Instruction::PWCTR3, Some(&[0x0A, 0x00]))?;
-        self.write_command(Instruction::PWCTR4, Some(&[0x8A, 0x2A]))?;
-        self.write_command(Instruction::PWCTR5, Some(&[0x8A, 0xEE]))?;
-        self.write_command(Instruction::VMCTR1, Some(&[0x0E]))?;
-        if self.inverted {
-            self.write_command(Instruction::INVON, None)?;
-        } else {
-            self.write_command(Instruction::INVOFF, None)?;
+    fn write_command(&mut self, command: Instruction, params: Option<&[u8]>) -> Result<(), DisplayError<SPI, DC>> {
+        self.finish_pixels()?;
+        self.command_seq = self.command_seq.wrapping_add(1);
+        if let (Instruction::MADCTL, Some(&[value, ..])) = (&command, params) {
+            self.madctl = value;
         }
-        if self.rgb {
-            self.write_command(Instruction::MADCTL, Some(&[0x00]))?;
-        } else {
-            self.write_command(Instruction::MADCTL, Some(&[0x08]))?;
+        self.dc.set_low().map_err(Error::Dc)?;
+        self.bus_write(&[command.to_u8().unwrap()], false)?;
+        if let Some(params) = params {
+            self.write_data(params)?;
         }
-        self.write_command(Instruction::COLMOD, Some(&[0x05]))?;
-        self.write_command(Instruction::DISPON, None)?;
-        delay.delay_ms(200);
         Ok(())
     }
 
-    pub fn hard_reset(&mut self) -> Result<(), ()>
-    {
-        self.rst.set_high().map_err(|_| ())?;
-        self.rst.set_low().map_err(|_| ())?;
-        self.rst.set_high().map_err(|_| ())
+    fn write_data(&mut self, data: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        self.dc.set_high().map_err(Error::Dc)?;
+        self.bus_write(data, true)
     }
 
-    fn write_command(&mut self, command: Instruction, params: Option<&[u8]>) -> Result<(), ()> {
-        self.dc.set_low().map_err(|_| ())?;
-        self.spi.write(&[command.to_u8().unwrap()]).map_err(|_| ())?;
-        if params.is_some() {
-            self.write_data(params.unwrap())?;
+    /// Writes bytes with DC already set, following the retry policy.
+    fn bus_write(&mut self, bytes: &[u8], data: bool) -> Result<(), DisplayError<SPI, DC>> {
+        let mut retries = self.retry.retries;
+        loop {
+            match self.spi.write(bytes) {
+                Ok(()) => return Ok(()),
+                Err(_) if retries > 0 => {
+                    retries -= 1;
+                    if self.retry.resync {
+                        let dc = if data { self.dc.set_high() } else { self.dc.set_low() };
+                        dc.map_err(Error::Dc)?;
+                    }
+                }
+                Err(e) => return Err(Error::Spi(e)),
+            }
+        }
+    }
+
+    /// Writes a data word to the display.
+    fn write_word(&mut self, value: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_data(&value.to_be_bytes())
+    }
+
+    /// Converts and writes a slice of pixels as data, a chunk at a time.
+    fn write_pixel_slice(&mut self, pixels: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        let mut converted = [0u16; CHUNK_PIXELS];
+        for chunk in pixels.chunks(converted.len()) {
+            for (out, &color) in converted.iter_mut().zip(chunk) {
+                *out = self.convert_color(color);
+            }
+            self.write_encoded(&converted[..chunk.len()])?;
         }
         Ok(())
     }
 
-    fn write_data(&mut self, data: &[u8]) -> Result<(), ()> {
-        self.dc.set_high().map_err(|_| ())?;
-        self.spi.write(data).map_err(|_| ())
+    /// Encodes converted pixels in the pixel format and writes them as data.
+    ///
+    /// RGB444 sends pixels in pairs, so an odd pixel is held back until the
+    /// next pixels or [`ST7735::finish_pixels`].
+    fn write_encoded(&mut self, pixels: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        let mut bytes = [0u8; CHUNK_PIXELS * 3];
+        match self.format {
+            PixelFormat::Rgb565 => {
+                for chunk in pixels.chunks(bytes.len() / 2) {
+                    let count = encode::to_be_bytes(chunk, &mut bytes);
+                    self.write_data(&bytes[..count * 2])?;
+                }
+            }
+            PixelFormat::Rgb666 => {
+                for chunk in pixels.chunks(bytes.len() / 3) {
+                    for (out, &color) in bytes.chunks_exact_mut(3).zip(chunk) {
+                        out.copy_from_slice(&encode::to_rgb666_bytes(color));
+                    }
+                    self.write_data(&bytes[..chunk.len() * 3])?;
+                }
+            }
+            PixelFormat::Rgb444 => {
+                let mut len = 0;
+                for &color in pixels {
+                    let color = encode::to_rgb444(color);
+                    let first = match self.pending.take() {
+                        Some(first) => first,
+                        None => {
+                            self.pending = Some(color);
+                            continue;
+                        }
+                    };
+                    let pair = (first as u32) << 12 | color as u32;
+                    bytes[len..len + 3].copy_from_slice(&pair.to_be_bytes()[1..]);
+                    len += 3;
+                    if len == bytes.len() {
+                        self.write_data(&bytes)?;
+                        len = 0;
+                    }
+                }
+                if len > 0 {
+                    self.write_data(&bytes[..len])?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    /// Writes a data word to the display.
-    fn write_word(&mut self, value: u16) -> Result<(), ()> {
-        let bytes: [u8; 2] = unsafe { transmute(value.to_be()) };
-        self.write_data(&bytes)
+    /// Sends an RGB444 pixel held back by `write_encoded`, if any.
+    ///
+    /// Its last four bits are padding, which the controller drops at the
+    /// end of the write.
+    fn finish_pixels(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        if let Some(color) = self.pending.take() {
+            self.write_data(&(color << 4).to_be_bytes())?;
+        }
+        Ok(())
     }
 
-    pub fn set_orientation(&mut self, orientation: &Orientation) -> Result<(), ()> {
-        if self.rgb {
-            self.write_command(
-                Instruction::MADCTL, Some(&[orientation.to_u8().unwrap()]
-            ))?;
-        } else {
-            self.write_command(
-                Instruction::MADCTL, Some(&[orientation.to_u8().unwrap() | 0x08 ]
-            ))?;
+    /// Applies the enabled color transforms to a pixel about to be sent.
+    fn convert_color(&self, mut color: u16) -> u16 {
+        if let Some(transform) = &self.transform {
+            color = transform.apply(color);
+        }
+        if let Some(scheme) = &self.high_contrast {
+            color = scheme.apply(color);
+        }
+        if let Some(night) = &self.night_mode {
+            color = night.apply(color);
+        }
+        color
+    }
+
+    /// Sets the orientation, swapping the width and height reported by
+    /// `size` when switching between portrait and landscape.
+    pub fn set_orientation(&mut self, orientation: &Orientation) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_madctl(*orientation, self.flags)
+    }
+
+    /// Mirrors the image horizontally and vertically on top of the
+    /// orientation.
+    pub fn set_mirror(&mut self, x: bool, y: bool) -> Result<(), DisplayError<SPI, DC>> {
+        let mut flags = MadctlFlags(self.flags.0 & !(MadctlFlags::MX.0 | MadctlFlags::MY.0));
+        if x {
+            flags = flags | MadctlFlags::MX;
+        }
+        if y {
+            flags = flags | MadctlFlags::MY;
+        }
+        self.write_madctl(self.orientation, flags)
+    }
+
+    /// Sets the MADCTL bits toggled on top of the orientation, replacing
+    /// those set before.
+    ///
+    /// Toggling [`MadctlFlags::MV`] swaps width and height like a change
+    /// between portrait and landscape.
+    pub fn set_madctl_flags(&mut self, flags: MadctlFlags) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_madctl(self.orientation, flags)
+    }
+
+    /// Returns the MADCTL bits toggled on top of the orientation.
+    pub fn madctl_flags(&self) -> MadctlFlags {
+        self.flags
+    }
+
+    /// Writes MADCTL for `orientation` with `flags` toggled, and loads the
+    /// size and offset that go with it.
+    fn write_madctl(&mut self, orientation: Orientation, flags: MadctlFlags) -> Result<(), DisplayError<SPI, DC>> {
+        let mut madctl = orientation.to_u8().unwrap() ^ flags.0;
+        if !self.rgb {
+            madctl |= 0x08;
+        }
+        self.write_command(Instruction::MADCTL, Some(&[madctl]))?;
+        self.follow_madctl(orientation, flags);
+        Ok(())
+    }
+
+    /// Loads the size and offset that go with `orientation` and `flags`.
+    fn follow_madctl(&mut self, orientation: Orientation, flags: MadctlFlags) {
+        let exchanged = (orientation.to_u8().unwrap() ^ flags.0) & MadctlFlags::MV.0 != 0;
+        if exchanged != self.rows_exchanged() {
+            core::mem::swap(&mut self.width, &mut self.height);
+        }
+        self.orientation = orientation;
+        self.flags = flags;
+        let (dx, dy) = self.offsets[orientation.index()];
+        self.dx = dx;
+        self.dy = dy;
+    }
+
+    /// Returns whether rows and columns are exchanged, i.e. `width` and
+    /// `height` are swapped with respect to portrait.
+    fn rows_exchanged(&self) -> bool {
+        (self.orientation.to_u8().unwrap() ^ self.flags.0) & MadctlFlags::MV.0 != 0
+    }
+
+    /// Returns the orientation last set.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Sets the signal on the tearing effect output.
+    ///
+    /// Starting a full-screen write when the TE pin rises keeps it behind
+    /// the refresh, avoiding visible tearing.
+    pub fn set_tearing_effect(&mut self, tearing_effect: TearingEffect) -> Result<(), DisplayError<SPI, DC>> {
+        match tearing_effect {
+            TearingEffect::Off => self.write_command(Instruction::TEOFF, None),
+            TearingEffect::Vertical => self.write_command(Instruction::TEON, Some(&[0x00])),
+            TearingEffect::HorizontalAndVertical => self.write_command(Instruction::TEON, Some(&[0x01])),
         }
+    }
+
+    /// Selects the pixel format sent to the panel.
+    ///
+    /// Pixels are still given as RGB565 and are reduced or expanded while
+    /// being sent. RGB444 packs two pixels in three bytes, cutting bus
+    /// traffic by a quarter at four bits per channel. Pre-encoded data, as
+    /// taken by `write_raw_iter` and `flush_buffer`, must match the format.
+    pub fn set_pixel_format(&mut self, format: PixelFormat) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::COLMOD, Some(&[format.colmod()]))?;
+        self.format = format;
+        Ok(())
+    }
+
+    /// Returns the pixel format sent to the panel.
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Switches color inversion on or off at once.
+    ///
+    /// The setting is kept, so later calls to `init` use it too. Useful to
+    /// flash the screen for alerts, or for panels found to need inversion
+    /// only after probing.
+    pub fn set_inversion(&mut self, inverted: bool) -> Result<(), DisplayError<SPI, DC>> {
+        let command = if inverted { Instruction::INVON } else { Instruction::INVOFF };
+        self.write_command(command, None)?;
+        self.inverted = inverted;
         Ok(())
     }
 
+    /// Returns whether colors are inverted.
+    pub fn inversion(&self) -> bool {
+        self.inverted
+    }
+
     /// Sets the global offset of the displayed image
+    ///
+    /// The offset is used in every orientation; panels that need a
+    /// different one when rotated can use `set_orientation_offset` or
+    /// `set_gram_offset` instead.
     pub fn set_offset(&mut self, dx: u16, dy: u16) {
+        self.offsets = [(dx, dy); 4];
         self.dx = dx;
         self.dy = dy;
     }
 
+    /// Sets the offset of the displayed image while in `orientation`.
+    pub fn set_orientation_offset(&mut self, orientation: Orientation, dx: u16, dy: u16) {
+        self.offsets[orientation.index()] = (dx, dy);
+        if orientation == self.orientation {
+            self.dx = dx;
+            self.dy = dy;
+        }
+    }
+
+    /// Derives the offset of every orientation from where the panel sits in
+    /// frame memory.
+    ///
+    /// `(dx, dy)` is the portrait offset, and `gram_width` by `gram_height`
+    /// the frame memory the controller is set up for, usually
+    /// [`scroll::FRAME_COLUMNS`] by [`scroll::FRAME_LINES`]. Mirrored axes
+    /// count the offset from the far edge of frame memory.
+    pub fn set_gram_offset(&mut self, dx: u16, dy: u16, gram_width: u16, gram_height: u16) {
+        let offsets = self.gram_offsets(dx, dy, gram_width, gram_height);
+        for orientation in [Orientation::Portrait, Orientation::Landscape, Orientation::PortraitSwapped, Orientation::LandscapeSwapped] {
+            let (dx, dy) = offsets[orientation.index()];
+            self.set_orientation_offset(orientation, dx, dy);
+        }
+    }
+
+    /// Returns the offset of every orientation, indexed as
+    /// [`Orientation::index`], as set by [`ST7735::set_gram_offset`].
+    fn gram_offsets(&self, dx: u16, dy: u16, gram_width: u16, gram_height: u16) -> [(u16, u16); 4] {
+        let (width, height) = self.portrait_size();
+        let mirrored_dx = gram_width.saturating_sub(width + dx);
+        let mirrored_dy = gram_height.saturating_sub(height + dy);
+        [(dx, dy), (dy, mirrored_dx), (mirrored_dx, mirrored_dy), (mirrored_dy, dx)]
+    }
+
+    /// Returns the `(width, height)` of the panel in portrait.
+    fn portrait_size(&self) -> (u16, u16) {
+        if self.rows_exchanged() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// Sets how failed command and data writes are retried.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Returns how failed command and data writes are retried.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    /// Registers a transform applied to every pixel written, or removes it.
+    ///
+    /// The transform runs before the high-contrast scheme and night mode,
+    /// so it suits theming, dimming and calibration.
+    pub fn set_color_transform(&mut self, transform: Option<ColorTransform>) {
+        self.transform = transform;
+    }
+
+    /// Returns the `(width, height)` of the panel in the current
+    /// orientation.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Sets how `set_pixel` and `set_pixels` handle coords outside the
+    /// panel. The default is to clip.
+    pub fn set_bounds_policy(&mut self, policy: BoundsPolicy) {
+        self.bounds = policy;
+    }
+
+    /// Returns how drawing outside the panel is handled.
+    pub fn bounds_policy(&self) -> BoundsPolicy {
+        self.bounds
+    }
+
+    /// Enables or disables the high-contrast color scheme.
+    ///
+    /// This applies to everything drawn afterwards; what is already on the
+    /// panel is left as it is.
+    pub fn set_high_contrast(&mut self, scheme: Option<HighContrast>) {
+        self.high_contrast = scheme;
+    }
+
+    /// Returns the active high-contrast color scheme.
+    pub fn high_contrast(&self) -> Option<HighContrast> {
+        self.high_contrast
+    }
+
+    /// Enables or disables night mode.
+    ///
+    /// Like the high-contrast scheme this only affects what is drawn
+    /// afterwards, and is applied after it.
+    pub fn set_night_mode(&mut self, night: Option<NightMode>) {
+        self.night_mode = night;
+    }
+
+    /// Returns the active night mode.
+    pub fn night_mode(&self) -> Option<NightMode> {
+        self.night_mode
+    }
+
     /// Sets the address window for the display.
-    fn set_address_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), ()> {
+    ///
+    /// Coords are inclusive and in the current orientation; the image
+    /// offset of that orientation is added before they are sent, so they
+    /// match those of `set_pixels`. They are not checked against the panel
+    /// size, but fail with [`Error::OutOfBounds`] if adding the offset
+    /// takes them past `u16::MAX`. Pixels written afterwards with
+    /// `write_pixels` or `write_raw_iter` fill the window row by row.
+    pub fn set_address_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let offset = |coord: u16, delta: u16| coord.checked_add(delta).ok_or(Error::OutOfBounds);
+        let (sx, ex) = (offset(sx, self.dx)?, offset(ex, self.dx)?);
+        let (sy, ey) = (offset(sy, self.dy)?, offset(ey, self.dy)?);
         self.write_command(Instruction::CASET, None)?;
-        self.write_word(sx + self.dx)?;
-        self.write_word(ex + self.dx)?;
+        self.write_word(sx)?;
+        self.write_word(ex)?;
         self.write_command(Instruction::RASET, None)?;
-        self.write_word(sy + self.dy)?;
-        self.write_word(ey + self.dy)
+        self.write_word(sy)?;
+        self.write_word(ey)
     }
 
     /// Sets a pixel color at the given coords.
-    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result <(), ()> {
+    ///
+    /// Coords are checked against the panel size in the current
+    /// orientation, never written to wrapped frame memory addresses. What
+    /// happens to ones outside depends on the [`BoundsPolicy`]; with
+    /// [`BoundsPolicy::Error`] they fail with [`Error::OutOfBounds`].
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let (x, y) = if x < self.width && y < self.height {
+            (x, y)
+        } else {
+            match self.bounds {
+                BoundsPolicy::Clip => return Ok(()),
+                BoundsPolicy::Error => return Err(Error::OutOfBounds),
+                BoundsPolicy::Wrap if self.width == 0 || self.height == 0 => return Ok(()),
+                BoundsPolicy::Wrap => (x % self.width, y % self.height),
+            }
+        };
         self.set_address_window(x, y, x, y)?;
         self.write_command(Instruction::RAMWR, None)?;
-        self.write_word(color)
+        self.write_encoded(&[self.convert_color(color)])?;
+        self.finish_pixels()
     }
 
     /// Writes pixel colors sequentially into the current drawing window
-    pub fn write_pixels<P: IntoIterator<Item = u16>>(&mut self, colors: P) -> Result <(), ()> {
+    pub fn write_pixels<P: IntoIterator<Item = u16>>(&mut self, colors: P) -> Result<(), DisplayError<SPI, DC>> {
         self.write_command(Instruction::RAMWR, None)?;
+        let mut converted = [0u16; CHUNK_PIXELS];
+        let mut len = 0;
         for color in colors {
-            self.write_word(color)?;
+            converted[len] = self.convert_color(color);
+            len += 1;
+            if len == converted.len() {
+                self.write_encoded(&converted)?;
+                len = 0;
+            }
+        }
+        self.write_encoded(&converted[..len])?;
+        self.finish_pixels()
+    }
+
+    /// Writes pre-encoded pixel bytes sequentially into the current drawing window
+    ///
+    /// The bytes are sent as they are, in chunks, so they must already be
+    /// in the panel's pixel format and byte order. Color transforms are not
+    /// applied.
+    pub fn write_raw_iter<B: IntoIterator<Item = u8>>(&mut self, bytes: B) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::RAMWR, None)?;
+        let mut chunk = [0u8; CHUNK_PIXELS * 2];
+        let mut len = 0;
+        for byte in bytes {
+            chunk[len] = byte;
+            len += 1;
+            if len == chunk.len() {
+                self.write_data(&chunk)?;
+                len = 0;
+            }
+        }
+        if len > 0 {
+            self.write_data(&chunk[..len])?;
         }
         Ok(())
     }
 
     /// Sets pixel colors at the given drawing window
-    pub fn set_pixels<P: IntoIterator<Item = u16>>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: P) -> Result <(), ()> {
+    pub fn set_pixels<P: IntoIterator<Item = u16>>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: P) -> Result<(), DisplayError<SPI, DC>> {
+        if ex < self.width && ey < self.height {
+            self.set_address_window(sx, sy, ex, ey)?;
+            return self.write_pixels(colors);
+        }
+        if sx > ex || sy > ey {
+            return Ok(());
+        }
+        let (width, height) = (self.width, self.height);
+        let window_width = ex as usize - sx as usize + 1;
+        let positioned = colors.into_iter().enumerate().map(move |(i, color)| {
+            let x = sx as usize + i % window_width;
+            let y = sy as usize + i / window_width;
+            (x, y, color)
+        });
+        match self.bounds {
+            BoundsPolicy::Error => Err(Error::OutOfBounds),
+            BoundsPolicy::Clip => {
+                if sx >= width || sy >= height {
+                    return Ok(());
+                }
+                let (cex, cey) = (ex.min(width - 1), ey.min(height - 1));
+                let visible = positioned
+                    .filter(|&(x, y, _)| x <= cex as usize && y <= cey as usize)
+                    .map(|(_, _, color)| color);
+                self.set_address_window(sx, sy, cex, cey)?;
+                self.write_pixels(visible)
+            }
+            BoundsPolicy::Wrap => {
+                if width == 0 || height == 0 {
+                    return Ok(());
+                }
+                let wrapped = positioned.take_while(|&(_, y, _)| y <= ey as usize).map(|(x, y, color)| {
+                    ((x % width as usize) as u16, (y % height as usize) as u16, color)
+                });
+                self.write_runs(wrapped)
+            }
+        }
+    }
+
+    /// Pushes a rectangle of row-major colors with a single window setup.
+    ///
+    /// Fails without drawing anything unless `colors` holds exactly
+    /// `rect.width * rect.height` pixels. Rectangles reaching outside the
+    /// panel follow the bounds policy like `set_pixels`, except that ones
+    /// reaching past `u16::MAX` fail with [`Error::OutOfBounds`].
+    pub fn flush_region(&mut self, rect: Region, colors: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        if colors.len() != rect.width as usize * rect.height as usize {
+            return Err(Error::InvalidArgument);
+        }
+        if colors.is_empty() {
+            return Ok(());
+        }
+        let (ex, ey) = rect.corner().ok_or(Error::OutOfBounds)?;
+        if ex >= self.width || ey >= self.height {
+            return self.set_pixels(rect.x, rect.y, ex, ey, colors.iter().copied());
+        }
+        self.set_address_window(rect.x, rect.y, ex, ey)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        self.write_pixel_slice(colors)?;
+        self.finish_pixels()
+    }
+
+    /// Pushes a window of pre-encoded pixel bytes in a single SPI write.
+    ///
+    /// `buf` must hold exactly the window's pixels encoded in the pixel
+    /// format, for RGB565 two bytes per pixel in the panel's byte order
+    /// (see [`encode::to_be_bytes`]). It is sent as it is, so images kept
+    /// in flash in that format need no conversion and a HAL can send them
+    /// as one DMA transfer. Color transforms are not applied, and the
+    /// window must lie on the panel.
+    pub fn flush_buffer(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, buf: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey || ex >= self.width || ey >= self.height {
+            return Err(Error::OutOfBounds);
+        }
+        let pixels = (ex as u64 - sx as u64 + 1) * (ey as u64 - sy as u64 + 1);
+        if buf.len() as u64 != self.format.bytes(pixels) {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        self.write_data(buf)
+    }
+
+    /// Like [`ST7735::flush_buffer`], but for little-endian RGB565 bytes
+    /// as written by most image converters.
+    ///
+    /// The bytes are swapped into the panel's order a chunk at a time while
+    /// being sent. Other pixel formats fail with [`Error::InvalidArgument`].
+    pub fn flush_buffer_le(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, buf: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey || ex >= self.width || ey >= self.height {
+            return Err(Error::OutOfBounds);
+        }
+        if self.format != PixelFormat::Rgb565 || buf.len() != 2 * (ex - sx + 1) as usize * (ey - sy + 1) as usize {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        let mut bytes = [0u8; CHUNK_PIXELS * 2];
+        for chunk in buf.chunks(bytes.len()) {
+            for (out, pair) in bytes.chunks_exact_mut(2).zip(chunk.chunks_exact(2)) {
+                out[0] = pair[1];
+                out[1] = pair[0];
+            }
+            self.write_data(&bytes[..chunk.len()])?;
+        }
+        Ok(())
+    }
+
+    /// Draws a rectangle of 8-bit palette indices, expanding them on the fly.
+    ///
+    /// Fails without drawing anything unless there is exactly one index per
+    /// pixel and every index is within `palette`.
+    pub fn draw_indexed(&mut self, rect: Region, indices: &[u8], palette: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        if indices.len() != rect.width as usize * rect.height as usize
+            || indices.iter().any(|&i| i as usize >= palette.len())
+        {
+            return Err(Error::InvalidArgument);
+        }
+        if indices.is_empty() {
+            return Ok(());
+        }
+        let (ex, ey) = rect.corner().ok_or(Error::OutOfBounds)?;
+        if ex >= self.width || ey >= self.height {
+            let colors = indices.iter().map(|&i| palette[i as usize]);
+            return self.set_pixels(rect.x, rect.y, ex, ey, colors);
+        }
+        self.set_address_window(rect.x, rect.y, ex, ey)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        let mut expanded = [0u16; CHUNK_PIXELS];
+        for chunk in indices.chunks(expanded.len()) {
+            for (color, &i) in expanded.iter_mut().zip(chunk) {
+                *color = palette[i as usize];
+            }
+            self.write_pixel_slice(&expanded[..chunk.len()])?;
+        }
+        self.finish_pixels()
+    }
+
+    /// Fills a window with one color, converting and encoding it only once
+    /// per chunk. Windows reaching outside the panel follow the bounds
+    /// policy like `set_pixels`.
+    fn fill_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey {
+            return Ok(());
+        }
+        let count = (ex as usize - sx as usize + 1) * (ey as usize - sy as usize + 1);
+        if ex >= self.width || ey >= self.height {
+            return self.set_pixels(sx, sy, ex, ey, core::iter::repeat_n(color, count));
+        }
         self.set_address_window(sx, sy, ex, ey)?;
-        self.write_pixels(colors)
+        self.write_command(Instruction::RAMWR, None)?;
+        let converted = [self.convert_color(color); CHUNK_PIXELS];
+        let mut left = count;
+        while left > 0 {
+            let len = left.min(CHUNK_PIXELS);
+            self.write_encoded(&converted[..len])?;
+            left -= len;
+        }
+        self.finish_pixels()
+    }
+
+    /// Writes positioned pixels, sharing one window per horizontal run.
+    fn write_runs<P: Iterator<Item = (u16, u16, u16)>>(&mut self, pixels: P) -> Result<(), DisplayError<SPI, DC>> {
+        let mut run = [0u16; CHUNK_PIXELS];
+        let mut len = 0;
+        let (mut run_x, mut run_y) = (0, 0);
+        for (x, y, color) in pixels {
+            if len == run.len() || (len > 0 && (y != run_y || x != run_x + len as u16)) {
+                self.set_address_window(run_x, run_y, run_x + len as u16 - 1, run_y)?;
+                self.write_pixels(run[..len].iter().copied())?;
+                len = 0;
+            }
+            if len == 0 {
+                run_x = x;
+                run_y = y;
+            }
+            run[len] = color;
+            len += 1;
+        }
+        if len > 0 {
+            self.set_address_window(run_x, run_y, run_x + len as u16 - 1, run_y)?;
+            self.write_pixels(run[..len].iter().copied())?;
+        }
+        Ok(())
     }
 }
 
 
+// Reset errors share the pin error type of `Error`, so the reset pin has to
+// fail like the DC pin for these.
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    /// Runs commands to initialize the display.
+    pub fn init<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.init_with(delay, &[])
+    }
+
+    /// Initializes the display like `init`, then sends `commands`.
+    ///
+    /// Commands sent later override the built-in values, so clone panels
+    /// can tweak e.g. their `FRMCTR` or `PWCTR` settings this way.
+    pub fn init_with<DELAY>(&mut self, delay: &mut DELAY, commands: &[InitCommand]) -> Result<(), DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.init_model(delay, &model::ST7735R, commands)
+    }
+
+    /// Sends the driver's own settings and switches the display on, as
+    /// the last step of initialization.
+    fn send_settings(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let inverted = self.inverted;
+        self.set_inversion(inverted)?;
+        let orientation = self.orientation;
+        self.set_orientation(&orientation)?;
+        self.write_command(Instruction::COLMOD, Some(&[self.format.colmod()]))?;
+        if self.variant.is_some() {
+            self.write_command(Instruction::GMCTRP1, Some(&variant::GAMMA_POSITIVE))?;
+            self.write_command(Instruction::GMCTRN1, Some(&variant::GAMMA_NEGATIVE))?;
+            self.write_command(Instruction::NORON, None)?;
+        }
+        self.write_command(Instruction::DISPON, None)
+    }
+
+    /// Pulses the reset pin.
+    ///
+    /// Does nothing with [`NoResetPin`]; [`ST7735::init`] follows up with a
+    /// software reset either way.
+    pub fn hard_reset(&mut self) -> Result<(), DisplayError<SPI, DC>>
+    {
+        self.rst.set_high().map_err(Error::Rst)?;
+        self.rst.set_low().map_err(Error::Rst)?;
+        self.rst.set_high().map_err(Error::Rst)
+    }
+}
+
 #[cfg(feature = "graphics")]
-extern crate embedded_graphics;
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Point, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+/// Converts an embedded-graphics point to coords, if it is in `u16` range.
 #[cfg(feature = "graphics")]
-use self::embedded_graphics::{drawable::{Pixel, Dimensions}, pixelcolor::Rgb565, Drawing, SizedDrawing};
+pub(crate) fn point_coords(point: Point) -> Option<(u16, u16)> {
+    use core::convert::TryFrom;
+    Some((u16::try_from(point.x).ok()?, u16::try_from(point.y).ok()?))
+}
 
+/// Returns the window `(sx, sy, ex, ey)` covered by an embedded-graphics
+/// area, or `None` if it is empty or not in `u16` range.
 #[cfg(feature = "graphics")]
-impl<SPI, DC, RST> Drawing<Rgb565> for ST7735<SPI, DC, RST>
+pub(crate) fn area_window(area: &Rectangle) -> Option<(u16, u16, u16, u16)> {
+    let (sx, sy) = point_coords(area.top_left)?;
+    let (ex, ey) = point_coords(area.bottom_right()?)?;
+    Some((sx, sy, ex, ey))
+}
+
+#[cfg(feature = "graphics")]
+impl<SPI, DC, RST> OriginDimensions for ST7735<SPI, DC, RST>
 where
     SPI: spi::Write<u8>,
     DC: OutputPin,
     RST: OutputPin,
 {
-    fn draw<T>(&mut self, item_pixels: T)
-    where
-        T: IntoIterator<Item = Pixel<Rgb565>>,
-    {
-        for Pixel(coord, color) in item_pixels {
-            self.set_pixel(coord.0 as u16, coord.1 as u16, color.0).expect("pixel write failed");
-        }
+    /// Returns the panel size in the current orientation, which is also
+    /// what `bounding_box` and the alignment helpers lay out against.
+    fn size(&self) -> Size {
+        let (width, height) = ST7735::size(self);
+        Size::new(width as u32, height as u32)
     }
 }
 
 #[cfg(feature = "graphics")]
-impl<SPI, DC, RST> SizedDrawing<Rgb565> for ST7735<SPI, DC, RST>
+impl<SPI, DC, RST> DrawTarget for ST7735<SPI, DC, RST>
 where
     SPI: spi::Write<u8>,
     DC: OutputPin,
     RST: OutputPin,
 {
-    fn draw_sized<T>(&mut self, item_pixels: T)
+    type Color = Rgb565;
+    type Error = DisplayError<SPI, DC>;
+
+    /// Draws pixels, sharing one window between pixels that follow each
+    /// other on a row under [`BoundsPolicy::Clip`], and sending one window
+    /// per pixel otherwise.
+    ///
+    /// Negative coords follow the bounds policy like any other coords off
+    /// the panel, except that they are never wrapped.
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        if self.bounds == BoundsPolicy::Clip {
+            let (width, height) = (self.width, self.height);
+            let visible = pixels
+                .into_iter()
+                .filter_map(|Pixel(point, color)| point_coords(point).map(|(x, y)| (x, y, color.into_storage())))
+                .filter(|&(x, y, _)| x < width && y < height);
+            return self.write_runs(visible);
+        }
+        for Pixel(point, color) in pixels {
+            match point_coords(point) {
+                Some((x, y)) => self.set_pixel(x, y, color.into_storage())?,
+                None if self.bounds == BoundsPolicy::Error => return Err(Error::OutOfBounds),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills the area as a single window where it has `u16` coords.
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb565) -> Result<(), Self::Error> {
+        match area_window(area) {
+            Some((sx, sy, ex, ey)) => self.fill_window(sx, sy, ex, ey, color.into_storage()),
+            None => self.fill_contiguous(area, core::iter::repeat(color)),
+        }
+    }
+
+    /// Sends the area as a single window where it has `u16` coords.
+    ///
+    /// Under [`BoundsPolicy::Clip`] an area partly off the panel, even past
+    /// its top or left edge, is cut down to a single window of its visible
+    /// part.
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        let visible = area.intersection(&self.bounding_box());
+        if self.bounds == BoundsPolicy::Clip && visible != *area {
+            let (sx, sy, ex, ey) = match area_window(&visible) {
+                Some(window) => window,
+                None => return Ok(()),
+            };
+            let colors = area
+                .points()
+                .zip(colors)
+                .filter(|(point, _)| visible.contains(*point))
+                .map(|(_, color)| color.into_storage());
+            return self.set_pixels(sx, sy, ex, ey, colors);
+        }
+        match area_window(area) {
+            Some((sx, sy, ex, ey)) => {
+                let count = area.size.width as usize * area.size.height as usize;
+                let colors = colors.into_iter().take(count).map(|color| color.into_storage());
+                self.set_pixels(sx, sy, ex, ey, colors)
+            }
+            None => self.draw_iter(area.points().zip(colors).map(|(point, color)| Pixel(point, color))),
+        }
+    }
+}
+
+// `Drawing` has no way to report errors, so these impls panic when a write
+// fails. The `DrawTarget` impl of the `graphics` feature returns them.
+#[cfg(feature = "graphics-legacy")]
+mod legacy {
+    use super::*;
+
+    use embedded_graphics_legacy::{drawable::{Pixel, Dimensions}, pixelcolor::Rgb565, Drawing, SizedDrawing};
+
+    impl<SPI, DC, RST> Drawing<Rgb565> for ST7735<SPI, DC, RST>
     where
-        T: IntoIterator<Item = Pixel<Rgb565>> + Dimensions,
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
     {
-        // Get bounding box `Coord`s as `(u32, u32)`
-        let top_left = item_pixels.top_left();
-        let bottom_right = item_pixels.bottom_right();
+        fn draw<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(coord, color) in item_pixels {
+                if coord.0 > u16::MAX as u32 || coord.1 > u16::MAX as u32 {
+                    continue;
+                }
+                if self.set_pixel(coord.0 as u16, coord.1 as u16, color.0).is_err() {
+                    panic!("pixel write failed");
+                }
+            }
+        }
+    }
 
-        self.set_pixels(top_left.0 as u16, top_left.1 as u16,
-                        bottom_right.0 as u16, bottom_right.1 as u16,
-                        item_pixels.into_iter().map(|Pixel(_coord, color)| color.0)).expect("pixels write failed")
+    impl<SPI, DC, RST> SizedDrawing<Rgb565> for ST7735<SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw_sized<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>> + Dimensions,
+        {
+            let top_left = item_pixels.top_left();
+            let bottom_right = item_pixels.bottom_right();
+            // Items reaching past the top or left edge, or out of `u16`
+            // range, are clipped pixel by pixel instead of truncating the
+            // window coords.
+            let max = u16::MAX as i32;
+            if top_left.0 < 0 || top_left.1 < 0 || bottom_right.0 > max || bottom_right.1 > max {
+                return self.draw(item_pixels);
+            }
+
+            let result = self.set_pixels(top_left.0 as u16, top_left.1 as u16,
+                            bottom_right.0 as u16, bottom_right.1 as u16,
+                            item_pixels.into_iter().map(|Pixel(_coord, color)| color.0));
+            if result.is_err() {
+                panic!("pixels write failed");
+            }
+        }
     }
 }
@@ -0,0 +1,283 @@
+//! Scrollable list widget with a selected item.
+
+use crate::font::TextStyle;
+use crate::region::Region;
+use crate::scroll::FRAME_LINES;
+use crate::{DisplayError, ST7735};
+
+use core::iter::repeat_n;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Text styles of the rows of a [`List`].
+///
+/// Each row is filled edge to edge with the style's background, or black
+/// if it has none, so redrawn rows never show stale pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct ListStyle {
+    /// Style of unselected rows.
+    pub normal: TextStyle,
+
+    /// Style of the selected row.
+    pub selected: TextStyle,
+}
+
+/// A vertical list of single-line items inside a region.
+///
+/// Items are fetched by index from a callback when a row is drawn, so the
+/// list holds no labels itself. Every method that changes what is shown
+/// redraws only the rows whose content changed.
+///
+/// When the region spans the full width of the panel in portrait
+/// orientation without vertical mirroring, scrolling uses the controller's
+/// hardware scrolling: the rows move without being resent and only the
+/// newly exposed ones are drawn. The list then takes over the scroll area,
+/// which [`List::draw`] sets up. Elsewhere every visible row is redrawn
+/// when the list scrolls.
+pub struct List<F> {
+    region: Region,
+    style: ListStyle,
+    items: F,
+    count: usize,
+    selected: usize,
+    top: usize,
+    /// Whether `draw` set up hardware scrolling
+    hardware_scroll: bool,
+    /// Row slot of the region showing the top row, while hardware scrolling
+    shift: usize,
+}
+
+impl<'a, F> List<F>
+where
+    F: FnMut(usize) -> &'a str,
+{
+    /// Creates a list of `count` items, selecting and showing the first.
+    ///
+    /// Nothing is drawn until [`List::draw`] is called.
+    pub fn new(region: Region, style: ListStyle, count: usize, items: F) -> Self {
+        List {
+            region,
+            style,
+            items,
+            count,
+            selected: 0,
+            top: 0,
+            hardware_scroll: false,
+            shift: 0,
+        }
+    }
+
+    /// Restores a selection and scroll position, e.g. when recreating a
+    /// list for items that were shown before. Nothing is drawn.
+    pub fn with_position(mut self, selected: usize, top: usize) -> Self {
+        self.selected = selected.min(self.count.saturating_sub(1));
+        self.top = top.min(self.max_top());
+        self
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns whether the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns the index of the item in the top row.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Returns the number of rows that fit in the region.
+    pub fn visible_rows(&self) -> usize {
+        match self.style.normal.font.height {
+            0 => 0,
+            height => (self.region.height / height as u16) as usize,
+        }
+    }
+
+    /// Changes the number of items, e.g. after the backing data changed.
+    ///
+    /// The selection and scroll position are clamped to the new count.
+    /// Call [`List::draw`] afterwards to show the new items.
+    pub fn set_count(&mut self, count: usize) {
+        self.count = count;
+        self.selected = self.selected.min(count.saturating_sub(1));
+        self.top = self.top.min(self.max_top());
+    }
+
+    /// Redraws every row, and sets up hardware scrolling where it can be
+    /// used.
+    pub fn draw<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.shift = 0;
+        let scrolled = self.visible_rows() as u16 * self.row_height();
+        self.hardware_scroll = display.can_scroll_rows(self.region, scrolled);
+        if self.hardware_scroll {
+            let top_fixed = display.dy + self.region.y;
+            display.set_scroll_area(top_fixed, scrolled, FRAME_LINES - top_fixed - scrolled)?;
+            display.set_scroll_offset(top_fixed)?;
+        }
+        for row in 0..self.visible_rows() {
+            self.draw_row(display, row)?;
+        }
+        Ok(())
+    }
+
+    /// Selects item `index`, scrolling just far enough to show it.
+    ///
+    /// Only the rows of the old and new selected items are redrawn, and
+    /// those exposed by scrolling.
+    pub fn select<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, index: usize) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if self.count == 0 {
+            return Ok(());
+        }
+        let index = index.min(self.count - 1);
+        let previous = self.selected;
+        self.selected = index;
+
+        let rows = self.visible_rows();
+        let top = if index < self.top {
+            index
+        } else if rows > 0 && index >= self.top + rows {
+            index + 1 - rows
+        } else {
+            self.top
+        };
+        let drawn = self.move_top(display, top)?;
+        if previous != index {
+            for item in [previous, index] {
+                if item >= self.top && item < self.top + rows && !drawn.contains(&(item - self.top)) {
+                    self.draw_row(display, item - self.top)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Selects the next item, if any.
+    pub fn select_next<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.select(display, self.selected + 1)
+    }
+
+    /// Selects the previous item, if any.
+    pub fn select_previous<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.select(display, self.selected.saturating_sub(1))
+    }
+
+    /// Scrolls so that item `index` is in the top row, as far as possible.
+    ///
+    /// The selection is not changed. With hardware scrolling only the rows
+    /// scrolled into view are drawn, e.g. a single row for a single-step
+    /// move; otherwise every row is redrawn.
+    pub fn scroll_to<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, index: usize) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.move_top(display, index.min(self.max_top())).map(|_| ())
+    }
+
+    fn max_top(&self) -> usize {
+        self.count.saturating_sub(self.visible_rows())
+    }
+
+    fn row_height(&self) -> u16 {
+        self.style.normal.font.height as u16
+    }
+
+    /// Makes item `top` the top row and draws the rows that need it.
+    ///
+    /// Returns the range of visible rows that were drawn.
+    fn move_top<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, top: usize) -> Result<core::ops::Range<usize>, DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let rows = self.visible_rows();
+        let previous = core::mem::replace(&mut self.top, top);
+        let drawn = if top == previous {
+            0..0
+        } else if self.hardware_scroll && top.abs_diff(previous) < rows {
+            let steps = top.abs_diff(previous);
+            self.shift = if top > previous {
+                (self.shift + steps) % rows
+            } else {
+                (self.shift + rows - steps) % rows
+            };
+            let line = self.region.y + self.shift as u16 * self.row_height();
+            display.set_scroll_offset(display.dy + line)?;
+            if top > previous { rows - steps..rows } else { 0..steps }
+        } else {
+            0..rows
+        };
+        for row in drawn.clone() {
+            self.draw_row(display, row)?;
+        }
+        Ok(drawn)
+    }
+
+    /// Draws visible row `row`, padding it to the region width.
+    fn draw_row<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, row: usize) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let index = self.top + row;
+        let mut style = if index == self.selected { self.style.selected } else { self.style.normal };
+        let background = style.background.unwrap_or(0);
+        style.background = Some(background);
+
+        let font = style.font;
+        let (width, height) = (self.region.width, self.row_height());
+        let slot = match self.visible_rows() {
+            0 => row,
+            rows => (row + self.shift) % rows,
+        };
+        let y = self.region.y + slot as u16 * height;
+        let mut x = self.region.x;
+        if index < self.count && font.width > 0 {
+            let cols = (width / font.width as u16) as usize;
+            let label = (self.items)(index);
+            let end = label.char_indices().nth(cols).map_or(label.len(), |(i, _)| i);
+            x = display.draw_text(x, y, &label[..end], &style)?;
+        }
+
+        let ex = self.region.x + width;
+        if x < ex && height > 0 {
+            let count = (ex - x) as usize * height as usize;
+            display.set_pixels(x, y, ex - 1, y + height - 1, repeat_n(background, count))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! A `log` backend printing records on the panel.
+//!
+//! The logger serializes access to the driver with `critical-section`, so
+//! it also works on cores without atomic swaps such as the Cortex-M0; the
+//! application has to provide an implementation, e.g. the
+//! `critical-section-single-core` feature of `cortex-m`.
+//!
+//! There is no `defmt` backend: defmt leaves the formatting of records to
+//! the host, so they cannot be rendered on the device.
+
+use crate::font::{TextStyle, FONT_6X8};
+use crate::region::Region;
+use crate::scroll::FRAME_LINES;
+use crate::ST7735;
+
+use core::cell::{Cell, UnsafeCell};
+use core::fmt::{self, Write};
+
+use critical_section::Mutex;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// Background of the console lines.
+const BACKGROUND: u16 = 0x0000;
+
+/// Length of the rate limiting window, in milliseconds.
+const WINDOW_MS: u32 = 1000;
+
+/// Returns the text color of records at `level`.
+fn level_color(level: Level) -> u16 {
+    match level {
+        Level::Error => 0xF800,
+        Level::Warn => 0xFFE0,
+        Level::Info => 0xFFFF,
+        Level::Debug => 0x07FF,
+        Level::Trace => 0x8410,
+    }
+}
+
+/// Logs records as lines of text in a region of the panel.
+///
+/// Long records wrap onto further lines. Once the region is full, the
+/// log scrolls up by a line for every new one. Scrolling is done in
+/// hardware, so it needs a region spanning the full panel width in
+/// portrait orientation without vertical mirroring; the logger then takes
+/// over the controller's scroll area. In any other region output
+/// continues at the top instead, always blanking the line after the
+/// newest one so the end of the log stays easy to spot.
+///
+/// The logger owns the driver; use [`ScreenLogger::with_display`] to draw
+/// anything else. Records logged while the driver is in use, e.g. from an
+/// interrupt, are dropped rather than waited for, so logging never
+/// blocks. Drawing errors are ignored.
+pub struct ScreenLogger<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    state: UnsafeCell<State<SPI, DC, RST>>,
+    busy: Mutex<Cell<bool>>,
+    dropped: Mutex<Cell<u32>>,
+    level: LevelFilter,
+    rate: Option<(u32, fn() -> u32)>,
+}
+
+struct State<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: ST7735<SPI, DC, RST>,
+    region: Region,
+    /// Line the current record is written to
+    line: u16,
+    /// Line shown at the top of the region, while scrolling in hardware
+    top: u16,
+    /// Whether the first record set up the region, and whether it scrolls
+    /// in hardware
+    started: bool,
+    hardware_scroll: bool,
+    /// Start of the current rate limiting window and records logged in it
+    window: (u32, u32),
+}
+
+// Access to the state is serialized by `busy`, set in a critical section.
+unsafe impl<SPI, DC, RST> Sync for ScreenLogger<SPI, DC, RST>
+where
+    SPI: spi::Write<u8> + Send,
+    DC: OutputPin + Send,
+    RST: OutputPin + Send,
+{
+}
+
+impl<SPI, DC, RST> ScreenLogger<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a logger printing records up to `level` inside `region`.
+    pub fn new(display: ST7735<SPI, DC, RST>, region: Region, level: LevelFilter) -> Self {
+        ScreenLogger {
+            state: UnsafeCell::new(State {
+                display,
+                region,
+                line: 0,
+                top: 0,
+                started: false,
+                hardware_scroll: false,
+                window: (0, 0),
+            }),
+            busy: Mutex::new(Cell::new(false)),
+            dropped: Mutex::new(Cell::new(0)),
+            level,
+            rate: None,
+        }
+    }
+
+    /// Limits output to `per_second` records, timed by a millisecond clock.
+    ///
+    /// Records over the limit are counted and the count is printed before
+    /// the next record that gets through.
+    pub fn rate_limit(mut self, per_second: u32, now_ms: fn() -> u32) -> Self {
+        self.rate = Some((per_second, now_ms));
+        self
+    }
+
+    /// Installs the logger as the global `log` backend.
+    pub fn install(&'static self) -> Result<(), SetLoggerError>
+    where
+        Self: Log,
+    {
+        log::set_logger(self)?;
+        log::set_max_level(self.level);
+        Ok(())
+    }
+
+    /// Runs `f` with the driver, or returns `None` if it is in use.
+    pub fn with_display<R>(&self, f: impl FnOnce(&mut ST7735<SPI, DC, RST>) -> R) -> Option<R> {
+        self.lock(|state| f(&mut state.display))
+    }
+
+    fn lock<R>(&self, f: impl FnOnce(&mut State<SPI, DC, RST>) -> R) -> Option<R> {
+        if critical_section::with(|cs| self.busy.borrow(cs).replace(true)) {
+            return None;
+        }
+        // The flag was clear, so nothing else holds the state.
+        let result = f(unsafe { &mut *self.state.get() });
+        critical_section::with(|cs| self.busy.borrow(cs).set(false));
+        Some(result)
+    }
+
+    /// Returns whether the rate limit lets another record through.
+    fn admit(&self, state: &mut State<SPI, DC, RST>) -> bool {
+        let (per_second, now_ms) = match self.rate {
+            Some(rate) => rate,
+            None => return true,
+        };
+        let now = now_ms();
+        let (start, count) = state.window;
+        if now.wrapping_sub(start) >= WINDOW_MS {
+            state.window = (now, 1);
+            return true;
+        }
+        if count >= per_second {
+            return false;
+        }
+        state.window.1 += 1;
+        true
+    }
+}
+
+impl<SPI, DC, RST> Log for ScreenLogger<SPI, DC, RST>
+where
+    SPI: spi::Write<u8> + Send,
+    DC: OutputPin + Send,
+    RST: OutputPin + Send,
+{
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let written = self.lock(|state| {
+            if !self.admit(state) {
+                return false;
+            }
+            let dropped = critical_section::with(|cs| self.dropped.borrow(cs).replace(0));
+            if dropped > 0 {
+                let mut lines = Lines::new(state, level_color(Level::Warn));
+                let _ = write!(lines, "({} records dropped)", dropped);
+            }
+            let mut lines = Lines::new(state, level_color(record.level()));
+            let _ = write!(lines, "{} {}", record.level(), record.args());
+            true
+        });
+        if written != Some(true) {
+            critical_section::with(|cs| {
+                let dropped = self.dropped.borrow(cs);
+                dropped.set(dropped.get().saturating_add(1));
+            });
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Writes one record, wrapping and clearing lines as it goes.
+struct Lines<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    state: &'a mut State<SPI, DC, RST>,
+    style: TextStyle,
+    column: u16,
+}
+
+impl<'a, SPI, DC, RST> Lines<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Starts a record on the line after the previous one.
+    fn new(state: &'a mut State<SPI, DC, RST>, color: u16) -> Self {
+        let mut lines = Lines {
+            state,
+            style: TextStyle::new(&FONT_6X8, color).background(BACKGROUND),
+            column: 0,
+        };
+        if lines.state.started {
+            lines.advance();
+        } else {
+            lines.set_up();
+        }
+        lines.start_line();
+        lines
+    }
+
+    fn rows(&self) -> u16 {
+        self.state.region.height / self.style.font.height as u16
+    }
+
+    fn columns(&self) -> u16 {
+        self.state.region.width / self.style.font.width as u16
+    }
+
+    /// Sets up hardware scrolling of the region where it can be used.
+    fn set_up(&mut self) {
+        let scrolled = self.rows() * self.style.font.height as u16;
+        let state = &mut *self.state;
+        state.started = true;
+        state.hardware_scroll = state.display.can_scroll_rows(state.region, scrolled);
+        if state.hardware_scroll {
+            let top_fixed = state.display.dy + state.region.y;
+            let _ = state.display.set_scroll_area(top_fixed, scrolled, FRAME_LINES - top_fixed - scrolled);
+            let _ = state.display.set_scroll_offset(top_fixed);
+        }
+    }
+
+    /// Blanks the current line, and while not scrolling the next as well.
+    fn start_line(&mut self) {
+        let rows = self.rows();
+        if rows == 0 {
+            return;
+        }
+        let Region { x, y, width, .. } = self.state.region;
+        let height = self.style.font.height as u16;
+        let next = (self.state.line + 1) % rows;
+        let blanked = if self.state.hardware_scroll { 1 } else { 2 };
+        for &line in [self.state.line, next].iter().take(blanked) {
+            let top = y + line * height;
+            let _ = self.state.display.fill_window(x, top, x + width - 1, top + height - 1, BACKGROUND);
+        }
+        self.column = 0;
+    }
+
+    /// Moves on to the next line, scrolling the oldest one out of view
+    /// once the region is full.
+    fn advance(&mut self) {
+        let rows = self.rows();
+        if rows == 0 {
+            return;
+        }
+        let height = self.style.font.height as u16;
+        let state = &mut *self.state;
+        state.line = (state.line + 1) % rows;
+        if state.hardware_scroll && state.line == state.top {
+            state.top = (state.top + 1) % rows;
+            let line = state.display.dy + state.region.y + state.top * height;
+            let _ = state.display.set_scroll_offset(line);
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.advance();
+        self.start_line();
+    }
+}
+
+impl<'a, SPI, DC, RST> Write for Lines<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let (columns, rows) = (self.columns(), self.rows());
+        if columns == 0 || rows == 0 {
+            return Ok(());
+        }
+        for c in s.chars() {
+            if c == '\n' {
+                self.new_line();
+                continue;
+            }
+            if self.column == columns {
+                self.new_line();
+            }
+            let font = self.style.font;
+            let x = self.state.region.x + self.column * font.width as u16;
+            let y = self.state.region.y + self.state.line * font.height as u16;
+            let _ = self.state.display.draw_char(x, y, c, &self.style);
+            self.column += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! Scrolling text tickers driven by the controller's scroll registers.
+
+use crate::font::TextStyle;
+use crate::scroll::FRAME_LINES;
+use crate::{DisplayError, Error, MadctlFlags, ST7735};
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A line of text moving sideways through a band of panel columns.
+///
+/// Needs a landscape orientation, in which frame memory lines run across
+/// the panel, so that hardware scrolling moves the band sideways. Each
+/// [`Marquee::step`] only draws the columns of text that scroll into
+/// view, however long the text is.
+///
+/// Hardware scrolling moves whole frame memory lines, so everything in
+/// the band's columns moves along, not just the text row. The scroll
+/// offset is left as it is when the marquee is dropped.
+pub struct Marquee<'a, 't, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    text: &'t str,
+    style: TextStyle,
+    background: u16,
+    x: u16,
+    y: u16,
+    width: u16,
+    /// First frame memory line of the band
+    first_line: u16,
+    /// Whether panel columns run towards lower frame memory lines
+    reversed: bool,
+    /// Length of one pass of text and the gap after it, in columns
+    period: u32,
+    /// Column of the pass shown at the left edge of the band
+    position: u32,
+    /// Columns the band has been scrolled by, modulo its width
+    shift: u16,
+    speed: u16,
+}
+
+impl<'a, 't, SPI, DC, RST> Marquee<'a, 't, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a marquee showing `text` at row `y` of the `width` columns
+    /// starting at `x`, and draws its first frame.
+    ///
+    /// The text is followed by a gap as wide as the band before it comes
+    /// round again. The background of `style` defaults to black. Fails
+    /// with [`Error::InvalidArgument`] in portrait orientations and with
+    /// [`Error::OutOfBounds`] if the band is not on the panel.
+    pub fn new(
+        display: &'a mut ST7735<SPI, DC, RST>,
+        x: u16,
+        y: u16,
+        width: u16,
+        text: &'t str,
+        style: TextStyle,
+    ) -> Result<Self, DisplayError<SPI, DC>> {
+        if !display.rows_exchanged() {
+            return Err(Error::InvalidArgument);
+        }
+        let (panel_width, panel_height) = display.size();
+        let height = style.font.height as u16;
+        if height == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        if width == 0 || x as u32 + width as u32 > panel_width as u32 || y as u32 + height as u32 > panel_height as u32 {
+            return Err(Error::OutOfBounds);
+        }
+        let madctl = display.orientation().to_u8().unwrap_or(0) ^ display.madctl_flags().bits();
+        let reversed = madctl & MadctlFlags::MY.bits() != 0;
+        let first = display.dx as u32 + x as u32;
+        let first_line = if reversed {
+            (FRAME_LINES as u32).checked_sub(first + width as u32)
+        } else {
+            Some(first).filter(|first| first + width as u32 <= FRAME_LINES as u32)
+        };
+        let first_line = first_line.ok_or(Error::OutOfBounds)? as u16;
+
+        let background = style.background.unwrap_or(0x0000);
+        let text_width = text.chars().count() as u32 * style.font.width as u32;
+        let mut marquee = Marquee {
+            display,
+            text,
+            style,
+            background,
+            x,
+            y,
+            width,
+            first_line,
+            reversed,
+            period: text_width + width as u32,
+            position: 0,
+            shift: 0,
+            speed: 1,
+        };
+        marquee.display.set_scroll_area(first_line, width, FRAME_LINES - first_line - width)?;
+        marquee.display.set_scroll_offset(first_line)?;
+        for view in 0..width as u32 {
+            marquee.draw_column(view)?;
+        }
+        Ok(marquee)
+    }
+
+    /// Sets how many columns each [`Marquee::step`] moves the text by.
+    pub fn set_speed(&mut self, columns: u16) {
+        self.speed = columns;
+    }
+
+    /// Returns how many columns each step moves the text by.
+    pub fn speed(&self) -> u16 {
+        self.speed
+    }
+
+    /// Moves the text left by the speed, drawing the columns that come
+    /// into view at the right edge.
+    pub fn step(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let width = self.width as u32;
+        let speed = self.speed as u32;
+        self.position = (self.position + speed) % self.period;
+        self.shift = ((self.shift as u32 + speed) % width) as u16;
+        for view in width - speed.min(width)..width {
+            self.draw_column(view)?;
+        }
+        let offset = if self.reversed { (self.width - self.shift) % self.width } else { self.shift };
+        self.display.set_scroll_offset(self.first_line + offset)
+    }
+
+    /// Draws the pass column shown `view` columns from the left edge of
+    /// the band.
+    fn draw_column(&mut self, view: u32) -> Result<(), DisplayError<SPI, DC>> {
+        let font = self.style.font;
+        let (cw, height) = (font.width as u32, font.height);
+        let column = (self.position + view) % self.period;
+        let glyph = self.text.chars().nth((column / cw.max(1)) as usize).map(|c| font.glyph(c));
+        let gx = (column % cw.max(1)) as u8;
+        let (color, background) = (self.style.color, self.background);
+        let colors = (0..height).map(|gy| match glyph {
+            Some(glyph) if font.pixel(glyph, gx, gy) => color,
+            _ => background,
+        });
+        let x = self.x + ((self.shift as u32 + view) % self.width as u32) as u16;
+        self.display.set_pixels(x, self.y, x, self.y + height as u16 - 1, colors)
+    }
+}
@@ -0,0 +1,231 @@
+//! Hierarchical menus built on the list widget.
+
+use crate::font::TextStyle;
+use crate::list::{List, ListStyle};
+use crate::region::Region;
+use crate::{DisplayError, ST7735};
+
+use core::iter::repeat_n;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Separator drawn between the titles in the title bar.
+const SEPARATOR: &str = " > ";
+
+/// A titled page of menu items.
+#[derive(Clone, Copy, Debug)]
+pub struct Menu {
+    /// Title shown in the title bar.
+    pub title: &'static str,
+
+    /// Items of the page, top to bottom.
+    pub items: &'static [MenuItem],
+}
+
+/// One row of a [`Menu`].
+#[derive(Clone, Copy, Debug)]
+pub struct MenuItem {
+    /// Text of the row.
+    pub label: &'static str,
+
+    /// What entering the row does.
+    pub kind: MenuKind,
+}
+
+/// What entering a menu row does.
+#[derive(Clone, Copy, Debug)]
+pub enum MenuKind {
+    /// Opens a nested menu.
+    Submenu(&'static Menu),
+
+    /// Reports the given action id to the application.
+    Action(u16),
+}
+
+/// Input driving a [`MenuController`], e.g. from a rotary encoder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// Moves the selection down.
+    Next,
+
+    /// Moves the selection up.
+    Previous,
+
+    /// Opens the selected submenu or activates the selected action.
+    Enter,
+
+    /// Returns to the parent menu.
+    Back,
+}
+
+/// Styles of a menu's title bar and rows.
+#[derive(Clone, Copy, Debug)]
+pub struct MenuStyle {
+    /// Style of the title bar.
+    pub title: TextStyle,
+
+    /// Styles of the item rows.
+    pub list: ListStyle,
+}
+
+#[derive(Clone, Copy)]
+struct Level {
+    menu: &'static Menu,
+    selected: usize,
+    top: usize,
+}
+
+/// Navigates a menu tree at most `DEPTH` levels deep.
+///
+/// The top line of the region is a title bar with the path to the current
+/// menu, the rest lists its items. Moving the selection redraws only the
+/// two affected rows; entering or leaving a menu redraws the whole region.
+pub struct MenuController<const DEPTH: usize> {
+    region: Region,
+    style: MenuStyle,
+    levels: [Level; DEPTH],
+    depth: usize,
+}
+
+impl<const DEPTH: usize> MenuController<DEPTH> {
+    /// Creates a controller showing `root`. Nothing is drawn until
+    /// [`MenuController::draw`] is called.
+    pub fn new(region: Region, style: MenuStyle, root: &'static Menu) -> Self {
+        assert!(DEPTH > 0);
+        MenuController {
+            region,
+            style,
+            levels: [Level { menu: root, selected: 0, top: 0 }; DEPTH],
+            depth: 1,
+        }
+    }
+
+    /// Returns the menu currently shown.
+    pub fn current(&self) -> &'static Menu {
+        self.levels[self.depth - 1].menu
+    }
+
+    /// Returns the index of the selected item in the current menu.
+    pub fn selected(&self) -> usize {
+        self.levels[self.depth - 1].selected
+    }
+
+    /// Returns how many levels deep the current menu is, starting at 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Redraws the title bar and all rows.
+    pub fn draw<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.draw_title(display)?;
+        self.list().draw(display)
+    }
+
+    /// Handles one input event.
+    ///
+    /// Returns the action id when [`MenuEvent::Enter`] activates an item.
+    /// Submenus beyond `DEPTH` levels are not entered.
+    pub fn handle<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, event: MenuEvent) -> Result<Option<u16>, DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        match event {
+            MenuEvent::Next | MenuEvent::Previous => {
+                let mut list = self.list();
+                if event == MenuEvent::Next {
+                    list.select_next(display)?;
+                } else {
+                    list.select_previous(display)?;
+                }
+                let (selected, top) = (list.selected(), list.top());
+                let level = &mut self.levels[self.depth - 1];
+                level.selected = selected;
+                level.top = top;
+            }
+            MenuEvent::Enter => {
+                let item = match self.current().items.get(self.selected()) {
+                    Some(item) => *item,
+                    None => return Ok(None),
+                };
+                match item.kind {
+                    MenuKind::Action(id) => return Ok(Some(id)),
+                    MenuKind::Submenu(menu) if self.depth < DEPTH => {
+                        self.levels[self.depth] = Level { menu, selected: 0, top: 0 };
+                        self.depth += 1;
+                        self.draw(display)?;
+                    }
+                    MenuKind::Submenu(_) => {}
+                }
+            }
+            MenuEvent::Back => {
+                if self.depth > 1 {
+                    self.depth -= 1;
+                    self.draw(display)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Builds the list widget for the current menu below the title bar.
+    fn list(&self) -> List<impl FnMut(usize) -> &'static str> {
+        let level = self.levels[self.depth - 1];
+        let items = level.menu.items;
+        let (_, body) = self.region.split_top(self.style.title.font.height as u16);
+        List::new(body, self.style.list, items.len(), move |i| items[i].label)
+            .with_position(level.selected, level.top)
+    }
+
+    /// Draws the breadcrumb path, dropping outer levels that do not fit.
+    fn draw_title<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let mut style = self.style.title;
+        let background = style.background.unwrap_or(0);
+        style.background = Some(background);
+        let font = style.font;
+        let (width, height) = (self.region.width, font.height as u16);
+        if font.width == 0 || height == 0 {
+            return Ok(());
+        }
+        let cols = (width / font.width as u16) as usize;
+
+        let levels = &self.levels[..self.depth];
+        let length = |from: usize| {
+            levels[from..].iter().map(|l| l.menu.title.chars().count()).sum::<usize>()
+                + (levels.len() - from - 1) * SEPARATOR.len()
+        };
+        let first = (0..levels.len()).find(|&from| length(from) <= cols).unwrap_or(levels.len() - 1);
+
+        let (y, ex) = (self.region.y, self.region.x + width);
+        let mut x = self.region.x;
+        let mut remaining = cols;
+        for (i, level) in levels[first..].iter().enumerate() {
+            if i > 0 {
+                x = display.draw_text(x, y, SEPARATOR, &style)?;
+                remaining -= SEPARATOR.len();
+            }
+            let title = level.menu.title;
+            let end = title.char_indices().nth(remaining).map_or(title.len(), |(i, _)| i);
+            x = display.draw_text(x, y, &title[..end], &style)?;
+            remaining -= title[..end].chars().count();
+        }
+
+        if x < ex {
+            let count = (ex - x) as usize * height as usize;
+            display.set_pixels(x, y, ex - 1, y + height - 1, repeat_n(background, count))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Controllers of the ST7735 family.
+//!
+//! The driver core is the same across the family; models differ in the
+//! power, frame rate and gamma settings `init` sends. Pass a model to
+//! [`ST7735::init_model`] for controllers other than the ST7735R, or
+//! implement [`Model`] for further relatives. Size, offsets and color
+//! order belong to the module rather than the controller; set them with
+//! the constructor or a [`Variant`](crate::variant::Variant).
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, InitCommand, ST7735};
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A controller model driven by [`ST7735`].
+pub trait Model {
+    /// Returns the commands sent by `init` between waking the controller
+    /// and setting up inversion, orientation and pixel format.
+    fn init_commands(&self) -> &[InitCommand<'static>];
+}
+
+/// The ST7735R, whose settings [`ST7735::init`] sends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ST7735R;
+
+/// The ST7735S.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ST7735S;
+
+/// The ILI9163, found on many 1.44" modules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ILI9163;
+
+const ST7735R_INIT: [InitCommand<'static>; 10] = [
+    InitCommand::new(Instruction::FRMCTR1, &[0x01, 0x2C, 0x2D]),
+    InitCommand::new(Instruction::FRMCTR2, &[0x01, 0x2C, 0x2D]),
+    InitCommand::new(Instruction::FRMCTR3, &[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D]),
+    InitCommand::new(Instruction::INVCTR, &[0x07]),
+    InitCommand::new(Instruction::PWCTR1, &[0xA2, 0x02, 0x84]),
+    InitCommand::new(Instruction::PWCTR2, &[0xC5]),
+    InitCommand::new(Instruction::PWCTR3, &[0x0A, 0x00]),
+    InitCommand::new(Instruction::PWCTR4, &[0x8A, 0x2A]),
+    InitCommand::new(Instruction::PWCTR5, &[0x8A, 0xEE]),
+    InitCommand::new(Instruction::VMCTR1, &[0x0E]),
+];
+
+const ST7735S_INIT: [InitCommand<'static>; 12] = [
+    InitCommand::new(Instruction::FRMCTR1, &[0x05, 0x3C, 0x3C]),
+    InitCommand::new(Instruction::FRMCTR2, &[0x05, 0x3C, 0x3C]),
+    InitCommand::new(Instruction::FRMCTR3, &[0x05, 0x3C, 0x3C, 0x05, 0x3C, 0x3C]),
+    InitCommand::new(Instruction::INVCTR, &[0x03]),
+    InitCommand::new(Instruction::PWCTR1, &[0x28, 0x08, 0x04]),
+    InitCommand::new(Instruction::PWCTR2, &[0xC0]),
+    InitCommand::new(Instruction::PWCTR3, &[0x0D, 0x00]),
+    InitCommand::new(Instruction::PWCTR4, &[0x8D, 0x2A]),
+    InitCommand::new(Instruction::PWCTR5, &[0x8D, 0xEE]),
+    InitCommand::new(Instruction::VMCTR1, &[0x1A]),
+    InitCommand::new(
+        Instruction::GMCTRP1,
+        &[0x04, 0x22, 0x07, 0x0A, 0x2E, 0x30, 0x25, 0x2A, 0x28, 0x26, 0x2E, 0x3A, 0x00, 0x01, 0x03, 0x13],
+    ),
+    InitCommand::new(
+        Instruction::GMCTRN1,
+        &[0x04, 0x16, 0x06, 0x0D, 0x2D, 0x26, 0x23, 0x27, 0x27, 0x25, 0x2D, 0x3B, 0x00, 0x01, 0x04, 0x13],
+    ),
+];
+
+const ILI9163_INIT: [InitCommand<'static>; 7] = [
+    InitCommand::new(Instruction::FRMCTR1, &[0x08, 0x08]),
+    InitCommand::new(Instruction::INVCTR, &[0x07]),
+    InitCommand::new(Instruction::PWCTR1, &[0x0A, 0x02]),
+    InitCommand::new(Instruction::PWCTR2, &[0x02]),
+    InitCommand::new(Instruction::VMCTR1, &[0x50, 0x5B]),
+    InitCommand::new(
+        Instruction::GMCTRP1,
+        &[0x3F, 0x25, 0x1C, 0x1E, 0x20, 0x12, 0x2A, 0x90, 0x24, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00],
+    ),
+    InitCommand::new(
+        Instruction::GMCTRN1,
+        &[0x20, 0x20, 0x20, 0x20, 0x05, 0x00, 0x15, 0xA7, 0x3D, 0x18, 0x25, 0x2A, 0x2B, 0x2B, 0x3A],
+    ),
+];
+
+impl Model for ST7735R {
+    fn init_commands(&self) -> &[InitCommand<'static>] {
+        &ST7735R_INIT
+    }
+}
+
+impl Model for ST7735S {
+    fn init_commands(&self) -> &[InitCommand<'static>] {
+        &ST7735S_INIT
+    }
+}
+
+impl Model for ILI9163 {
+    fn init_commands(&self) -> &[InitCommand<'static>] {
+        &ILI9163_INIT
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    /// Initializes the display like [`ST7735::init_with`], but with the
+    /// settings of `model` instead of the ST7735R ones.
+    pub fn init_model<DELAY, M>(&mut self, delay: &mut DELAY, model: &M, commands: &[InitCommand]) -> Result<(), DisplayError<SPI, DC>>
+    where
+        DELAY: DelayMs<u8>,
+        M: Model + ?Sized,
+    {
+        for wait_ms in self.init_sequence_with(model, commands) {
+            let wait_ms = wait_ms?;
+            if wait_ms > 0 {
+                delay.delay_ms(wait_ms);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+//! Showing panic reports on the panel.
+
+use crate::font::{TextStyle, FONT_6X8};
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::text::{TextWriter, WrapStyle};
+use crate::{BoundsPolicy, DisplayError, ST7735};
+
+use core::fmt::Write;
+use core::panic::PanicInfo;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Background of the panic screen (dark red).
+const BACKGROUND: u16 = 0x8000;
+
+/// Text color of the panic screen.
+const FOREGROUND: u16 = 0xFFFF;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Shows the panic message and location full screen.
+    ///
+    /// Meant to be called from a `#[panic_handler]` that owns or can steal
+    /// the driver. Everything that could hide the report is undone first:
+    /// color transforms are dropped, out of bounds drawing is clipped, an
+    /// RGB444 pixel held back by an interrupted write is discarded, pixel
+    /// format and orientation are sent again, and sleep, scrolling, partial
+    /// mode, idle mode and display off are left. Waking the panel takes
+    /// 120 ms, waited with `delay`. Text that does not fit ends in an
+    /// ellipsis.
+    pub fn display_panic<DELAY>(&mut self, info: &PanicInfo, delay: &mut DELAY) -> Result<(), DisplayError<SPI, DC>>
+    where
+        DELAY: DelayMs<u8>,
+    {
+        self.bounds = BoundsPolicy::Clip;
+        self.transform = None;
+        self.high_contrast = None;
+        self.night_mode = None;
+        self.pending = None;
+        self.wake(delay)?;
+        self.write_command(Instruction::COLMOD, Some(&[self.format.colmod()]))?;
+        self.write_madctl(self.orientation, self.flags)?;
+        self.write_command(Instruction::NORON, None)?;
+        self.set_idle_mode(false)?;
+        self.write_command(Instruction::DISPON, None)?;
+
+        let (width, height) = self.size();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        self.fill_window(0, 0, width - 1, height - 1, BACKGROUND)?;
+
+        let style = WrapStyle::new(TextStyle::new(&FONT_6X8, FOREGROUND).background(BACKGROUND)).ellipsis(true);
+        let mut writer = TextWriter::new(self, Region::new(0, 0, width, height), style);
+        // A failing `Display` impl only cuts the message short.
+        let _ = write!(writer, "{}", info);
+        match writer.take_error() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
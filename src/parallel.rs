@@ -0,0 +1,93 @@
+//! 8-bit parallel (8080) bus on GPIO pins.
+//!
+//! Boards wired for the controller's 8080 interface put a byte on eight
+//! data lines and latch it with a rising edge of WR, with DC selecting
+//! commands or data as on SPI. [`ParallelBus`] stands in for the SPI bus,
+//! so the driver is used as usual with the DC pin:
+//!
+//! ```ignore
+//! let bus = ParallelBus::new([d0, d1, d2, d3, d4, d5, d6, d7], wr)?;
+//! let mut display = ST7735::new(bus, dc, rst, true, false, 128, 160);
+//! ```
+//!
+//! RD must be tied high, as nothing is read back.
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Eight data lines set to a byte at once.
+///
+/// Implemented for arrays of pins with D0 first. Implementing it on a
+/// GPIO port register writes all lines in one go, which is much faster.
+pub trait DataBus {
+    type Error;
+
+    /// Drives the lines with `value`, D0 being the least significant bit.
+    fn set_value(&mut self, value: u8) -> Result<(), Self::Error>;
+}
+
+impl<P: OutputPin> DataBus for [P; 8] {
+    type Error = P::Error;
+
+    fn set_value(&mut self, value: u8) -> Result<(), P::Error> {
+        for (bit, pin) in self.iter_mut().enumerate() {
+            if value & (1 << bit) != 0 {
+                pin.set_high()?;
+            } else {
+                pin.set_low()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error of a [`ParallelBus`] write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParallelError<BusE, WrE> {
+    /// Setting the data lines failed.
+    Bus(BusE),
+
+    /// Setting the WR pin failed.
+    Wr(WrE),
+}
+
+/// An 8080 write-only bus standing in for SPI.
+pub struct ParallelBus<BUS, WR> {
+    bus: BUS,
+    wr: WR,
+}
+
+impl<BUS, WR> ParallelBus<BUS, WR>
+where
+    BUS: DataBus,
+    WR: OutputPin,
+{
+    /// Creates the bus, setting WR high.
+    pub fn new(bus: BUS, mut wr: WR) -> Result<Self, WR::Error> {
+        wr.set_high()?;
+        Ok(ParallelBus { bus, wr })
+    }
+
+    /// Returns the data lines and WR pin.
+    pub fn release(self) -> (BUS, WR) {
+        (self.bus, self.wr)
+    }
+}
+
+impl<BUS, WR> spi::Write<u8> for ParallelBus<BUS, WR>
+where
+    BUS: DataBus,
+    WR: OutputPin,
+{
+    type Error = ParallelError<BUS::Error, WR::Error>;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for &word in words {
+            self.wr.set_low().map_err(ParallelError::Wr)?;
+            self.bus.set_value(word).map_err(ParallelError::Bus)?;
+            self.wr.set_high().map_err(ParallelError::Wr)?;
+        }
+        Ok(())
+    }
+}
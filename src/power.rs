@@ -0,0 +1,91 @@
+//! Power saving modes of the controller.
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, ST7735};
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Time the controller needs after entering or leaving sleep before it
+/// may be woken up or put to sleep again.
+const SLEEP_SETTLE_MS: u8 = 120;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Puts the panel to sleep, turning off its booster and oscillator.
+    ///
+    /// Frame memory and all settings are kept, so drawing may go on while
+    /// asleep and shows after [`ST7735::wake`].
+    pub fn sleep<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.write_command(Instruction::SLPIN, None)?;
+        delay.delay_ms(SLEEP_SETTLE_MS);
+        Ok(())
+    }
+
+    /// Wakes the panel from [`ST7735::sleep`] without initializing it again.
+    pub fn wake<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.write_command(Instruction::SLPOUT, None)?;
+        delay.delay_ms(SLEEP_SETTLE_MS);
+        Ok(())
+    }
+
+    /// Shows frame memory on the panel again after [`ST7735::display_off`].
+    pub fn display_on(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::DISPON, None)
+    }
+
+    /// Blanks the panel while keeping frame memory, which can still be
+    /// drawn to.
+    pub fn display_off(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::DISPOFF, None)
+    }
+
+    /// Switches idle mode on or off.
+    ///
+    /// In idle mode the panel shows only the top bit of each color
+    /// channel, i.e. eight colors, and draws less power. Frame memory keeps
+    /// full color, so drawing goes on as usual and everything shows in full
+    /// color again once idle mode is left.
+    pub fn set_idle_mode(&mut self, idle: bool) -> Result<(), DisplayError<SPI, DC>> {
+        let command = if idle { Instruction::IDMON } else { Instruction::IDMOFF };
+        self.write_command(command, None)?;
+        self.idle = idle;
+        Ok(())
+    }
+
+    /// Returns whether idle mode is on.
+    pub fn idle_mode(&self) -> bool {
+        self.idle
+    }
+
+    /// Limits the refresh to rows `start_row..=end_row` and enters partial
+    /// mode, leaving the rest of the panel blank.
+    ///
+    /// Rows are panel rows in the default orientation, counted from the
+    /// top, and the area may wrap around the bottom of frame memory when
+    /// `start_row` is after `end_row`. Leave with [`ST7735::normal_mode`].
+    pub fn set_partial_area(&mut self, start_row: u16, end_row: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let rows = if self.rows_exchanged() { self.width } else { self.height };
+        if start_row >= rows || end_row >= rows {
+            return Err(Error::OutOfBounds);
+        }
+        self.write_command(Instruction::PTLAR, None)?;
+        self.write_word(start_row + self.dy)?;
+        self.write_word(end_row + self.dy)?;
+        self.write_command(Instruction::PTLON, None)
+    }
+
+    /// Leaves partial mode and refreshes the whole panel again.
+    pub fn normal_mode(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::NORON, None)
+    }
+}
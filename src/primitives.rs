@@ -0,0 +1,84 @@
+//! Basic drawing primitives for builds without embedded-graphics.
+//!
+//! Lines and filled rectangles open one window and stream a single color
+//! into it, rather than setting pixels one by one.
+//!
+//! Shapes reaching past `u16::MAX` are cut off there, which no panel
+//! reaches, or fail under [`BoundsPolicy::Error`].
+
+use crate::{BoundsPolicy, DisplayError, Error, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws a horizontal line of `len` pixels starting at `(x, y)`.
+    pub fn draw_hline(&mut self, x: u16, y: u16, len: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.fill_rect(x, y, len, 1, color)
+    }
+
+    /// Draws a vertical line of `len` pixels starting at `(x, y)`.
+    pub fn draw_vline(&mut self, x: u16, y: u16, len: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.fill_rect(x, y, 1, len, color)
+    }
+
+    /// Fills a `width` x `height` rectangle with its top left at `(x, y)`.
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (width, height) = self.clip_rect(x, y, width, height)?;
+        self.fill_window(x, y, x + (width - 1), y + (height - 1), color)
+    }
+
+    /// Draws the 1 pixel wide outline of a rectangle.
+    pub fn draw_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let right = x.checked_add(width - 1);
+        let bottom = y.checked_add(height - 1);
+        if (right.is_none() || bottom.is_none()) && self.bounds == BoundsPolicy::Error {
+            return Err(Error::OutOfBounds);
+        }
+        self.draw_hline(x, y, width, color)?;
+        if let (true, Some(bottom)) = (height > 1, bottom) {
+            self.draw_hline(x, bottom, width, color)?;
+        }
+        if let (true, Some(top)) = (height > 2, y.checked_add(1)) {
+            self.draw_vline(x, top, height - 2, color)?;
+            if let (true, Some(right)) = (width > 1, right) {
+                self.draw_vline(right, top, height - 2, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a `width` x `height` image of row-major RGB565 pixels.
+    pub fn draw_image(&mut self, x: u16, y: u16, width: u16, height: u16, pixels: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (visible_width, visible_height) = self.clip_rect(x, y, width, height)?;
+        let rows = pixels.chunks(width as usize).take(visible_height as usize);
+        let visible = rows.flat_map(|row| row.iter().copied().take(visible_width as usize));
+        self.set_pixels(x, y, x + (visible_width - 1), y + (visible_height - 1), visible)
+    }
+
+    /// Returns the size of the part of a non-empty rectangle that lies
+    /// within `u16` coords, failing under [`BoundsPolicy::Error`] if that
+    /// is not all of it.
+    fn clip_rect(&self, x: u16, y: u16, width: u16, height: u16) -> Result<(u16, u16), DisplayError<SPI, DC>> {
+        let span = |start: u16, len: u16| (len as u32).min(u16::MAX as u32 - start as u32 + 1) as u16;
+        let visible = (span(x, width), span(y, height));
+        if visible != (width, height) && self.bounds == BoundsPolicy::Error {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(visible)
+    }
+}
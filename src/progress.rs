@@ -0,0 +1,113 @@
+//! Progress bar that redraws only what changed.
+
+use crate::region::Region;
+use crate::{DisplayError, ST7735};
+
+use core::iter::repeat_n;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A horizontal bar filling from the left as a value approaches its maximum.
+///
+/// Each update sends only the columns that changed state, so even very
+/// frequent updates cost a few pixel columns at most.
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressBar {
+    region: Region,
+    fill: u16,
+    empty: u16,
+    max: u32,
+    value: u32,
+    /// Filled width currently on the panel, `None` before the first draw
+    drawn: Option<u16>,
+}
+
+impl ProgressBar {
+    /// Creates an empty bar for values up to `max`. Nothing is drawn until
+    /// [`ProgressBar::draw`] or [`ProgressBar::set_value`] is called.
+    pub const fn new(region: Region, max: u32, fill: u16, empty: u16) -> Self {
+        ProgressBar {
+            region,
+            fill,
+            empty,
+            max,
+            value: 0,
+            drawn: None,
+        }
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns the value of a full bar.
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+
+    /// Redraws the whole bar.
+    pub fn draw<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let filled = self.filled_width();
+        self.fill_columns(display, 0, filled, self.fill)?;
+        self.fill_columns(display, filled, self.region.width, self.empty)?;
+        self.drawn = Some(filled);
+        Ok(())
+    }
+
+    /// Sets the value, clamped to the maximum, and updates the changed columns.
+    pub fn set_value<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, value: u32) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.value = value.min(self.max);
+        let drawn = match self.drawn {
+            Some(drawn) => drawn,
+            None => return self.draw(display),
+        };
+        let filled = self.filled_width();
+        if filled > drawn {
+            self.fill_columns(display, drawn, filled, self.fill)?;
+        } else if filled < drawn {
+            self.fill_columns(display, filled, drawn, self.empty)?;
+        }
+        self.drawn = Some(filled);
+        Ok(())
+    }
+
+    fn filled_width(&self) -> u16 {
+        if self.max == 0 {
+            return 0;
+        }
+        (self.region.width as u64 * self.value as u64 / self.max as u64) as u16
+    }
+
+    /// Fills region columns `from..to` with `color`.
+    fn fill_columns<SPI, DC, RST>(
+        &self,
+        display: &mut ST7735<SPI, DC, RST>,
+        from: u16,
+        to: u16,
+        color: u16,
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if from >= to || self.region.height == 0 {
+            return Ok(());
+        }
+        let Region { x, y, height, .. } = self.region;
+        let count = (to - from) as usize * height as usize;
+        display.set_pixels(x + from, y, x + to - 1, y + height - 1, repeat_n(color, count))
+    }
+}
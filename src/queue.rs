@@ -0,0 +1,156 @@
+//! Prioritized queue of pending flushes.
+
+use crate::flush::Flush;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+struct Job<'a> {
+    priority: u8,
+    order: u32,
+    flush: Flush<'a>,
+}
+
+/// A fixed-capacity queue of flushes serviced by priority.
+///
+/// Every poll sends one chunk of the most urgent job, so an alarm banner
+/// pushed while a full-screen redraw is in progress goes out after at most
+/// one chunk and the redraw then resumes where it left off.
+pub struct RenderQueue<'a, const N: usize> {
+    jobs: [Option<Job<'a>>; N],
+    next_order: u32,
+}
+
+impl<'a, const N: usize> RenderQueue<'a, N> {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        RenderQueue {
+            jobs: core::array::from_fn(|_| None),
+            next_order: 0,
+        }
+    }
+
+    /// Queues a flush, higher `priority` values being serviced first.
+    ///
+    /// Jobs of equal priority run in the order they were pushed. If the
+    /// queue is full the flush is handed back.
+    pub fn push(&mut self, priority: u8, flush: Flush<'a>) -> Result<(), Flush<'a>> {
+        match self.jobs.iter_mut().find(|job| job.is_none()) {
+            Some(slot) => {
+                *slot = Some(Job {
+                    priority,
+                    order: self.next_order,
+                    flush,
+                });
+                self.next_order = self.next_order.wrapping_add(1);
+                Ok(())
+            }
+            None => Err(flush),
+        }
+    }
+
+    /// Returns the number of queued jobs.
+    pub fn len(&self) -> usize {
+        self.jobs.iter().filter(|job| job.is_some()).count()
+    }
+
+    /// Returns whether no jobs are queued.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.iter().all(|job| job.is_none())
+    }
+
+    /// Returns the number of pixels still to be sent by all queued jobs.
+    pub fn remaining(&self) -> usize {
+        self.jobs.iter().flatten().map(|job| job.flush.remaining()).sum()
+    }
+
+    /// Drops every queued job.
+    pub fn clear(&mut self) {
+        self.jobs.iter_mut().for_each(|job| *job = None);
+    }
+
+    /// Returns the slot of the most urgent job.
+    fn next(&self) -> Option<usize> {
+        let mut best: Option<(usize, &Job)> = None;
+        for (i, job) in self.jobs.iter().enumerate() {
+            if let Some(job) = job {
+                let better = match best {
+                    None => true,
+                    Some((_, b)) => {
+                        job.priority > b.priority
+                            || (job.priority == b.priority
+                                && job.order.wrapping_sub(b.order) > u32::MAX / 2)
+                    }
+                };
+                if better {
+                    best = Some((i, job));
+                }
+            }
+        }
+        best.map(|(i, _)| i)
+    }
+}
+
+impl<'a, const N: usize> Default for RenderQueue<'a, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sends the next chunk of the most urgent job in `queue`.
+    ///
+    /// Returns `WouldBlock` until the queue has been drained.
+    pub fn poll_queue<const N: usize>(&mut self, queue: &mut RenderQueue<N>) -> nb::Result<(), DisplayError<SPI, DC>> {
+        let slot = match queue.next() {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+        if let Some(job) = &mut queue.jobs[slot] {
+            match self.poll_flush(&mut job.flush) {
+                Ok(()) => queue.jobs[slot] = None,
+                Err(nb::Error::WouldBlock) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if queue.is_empty() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Services `queue` until it is drained or `budget_us` has elapsed.
+    ///
+    /// `now_us` returns a free-running microsecond timestamp, which may
+    /// wrap. The budget is checked after every chunk, so it can be
+    /// overrun by the time one chunk takes; pick the jobs' chunk size with
+    /// that in mind. Returns the number of pixels left in the queue.
+    pub fn render_for<const N: usize, F>(
+        &mut self,
+        queue: &mut RenderQueue<N>,
+        budget_us: u32,
+        mut now_us: F,
+    ) -> Result<usize, DisplayError<SPI, DC>>
+    where
+        F: FnMut() -> u32,
+    {
+        let start = now_us();
+        loop {
+            match self.poll_queue(queue) {
+                Ok(()) => return Ok(0),
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            if now_us().wrapping_sub(start) >= budget_us {
+                return Ok(queue.remaining());
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Smooth brightness transitions driven by periodic ticks.
+
+/// Shape of a brightness transition over time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant rate of change.
+    Linear,
+
+    /// Starts slowly and speeds up.
+    EaseIn,
+
+    /// Starts quickly and slows down towards the target.
+    EaseOut,
+
+    /// Slow at both ends (smoothstep).
+    EaseInOut,
+}
+
+/// Fixed point one for eased progress.
+const ONE: u32 = 1 << 16;
+
+impl Easing {
+    /// Maps linear progress `t` (0..=ONE) onto eased progress (0..=ONE).
+    fn apply(self, t: u32) -> u32 {
+        let t = t.min(ONE) as u64;
+        let one = ONE as u64;
+        let eased = match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t / one,
+            Easing::EaseOut => one - (one - t) * (one - t) / one,
+            Easing::EaseInOut => (3 * t * t * one - 2 * t * t * t) / (one * one),
+        };
+        eased as u32
+    }
+}
+
+/// Moves a brightness level towards a target without blocking.
+///
+/// Call [`BrightnessRamp::tick`] periodically with the time elapsed since
+/// the previous call and apply the returned level to the backlight (or use
+/// it to dim colors in software).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BrightnessRamp {
+    from: u8,
+    to: u8,
+    level: u8,
+    duration_ms: u32,
+    elapsed_ms: u32,
+    easing: Easing,
+}
+
+impl BrightnessRamp {
+    /// Creates a ramp resting at `level`.
+    pub const fn new(level: u8) -> Self {
+        BrightnessRamp {
+            from: level,
+            to: level,
+            level,
+            duration_ms: 0,
+            elapsed_ms: 0,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Starts moving from the current level to `target` over `duration_ms`.
+    pub fn set_target(&mut self, target: u8, duration_ms: u32, easing: Easing) {
+        self.from = self.level;
+        self.to = target;
+        self.duration_ms = duration_ms;
+        self.elapsed_ms = 0;
+        self.easing = easing;
+        if duration_ms == 0 {
+            self.level = target;
+        }
+    }
+
+    /// Advances the ramp by `dt_ms` and returns the new level.
+    pub fn tick(&mut self, dt_ms: u32) -> u8 {
+        if self.is_done() {
+            return self.level;
+        }
+        self.elapsed_ms = self.elapsed_ms.saturating_add(dt_ms).min(self.duration_ms);
+        let t = (self.elapsed_ms as u64 * ONE as u64 / self.duration_ms as u64) as u32;
+        let eased = self.easing.apply(t) as i32;
+        let delta = self.to as i32 - self.from as i32;
+        self.level = (self.from as i32 + delta * eased / ONE as i32) as u8;
+        if self.elapsed_ms == self.duration_ms {
+            self.level = self.to;
+        }
+        self.level
+    }
+
+    /// Returns the current level.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Returns the level being moved towards.
+    pub fn target(&self) -> u8 {
+        self.to
+    }
+
+    /// Returns whether the target has been reached.
+    pub fn is_done(&self) -> bool {
+        self.level == self.to && self.elapsed_ms >= self.duration_ms
+    }
+}
@@ -0,0 +1,173 @@
+//! Reading registers and frame memory back from the controller.
+//!
+//! All reads are full duplex: the bus clocks out zero bytes while the
+//! controller's answer is clocked in, so they run at the full SPI speed.
+//!
+//! Some commands answer after a single dummy clock. The bus only moves
+//! whole bytes, so one extra byte is read and the answer shifted back into
+//! place.
+
+use crate::color::rgb666_to_rgb565;
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, CHUNK_PIXELS, ST7735};
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Returns the number of bytes `command` answers with and whether a dummy
+/// clock precedes them, or `None` if it is not a register read.
+fn read_format(command: &Instruction) -> Option<(usize, bool)> {
+    match command {
+        Instruction::RDDID => Some((3, true)),
+        Instruction::RDDST => Some((4, true)),
+        Instruction::RDDPM
+        | Instruction::RDDMADCTL
+        | Instruction::RDDCOLMOD
+        | Instruction::RDDIM
+        | Instruction::RDDSM
+        | Instruction::RDID1
+        | Instruction::RDID2
+        | Instruction::RDID3 => Some((1, false)),
+        _ => None,
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8> + spi::Transfer<u8, Error = <SPI as spi::Write<u8>>::Error>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sends a register read command and reads its answer into `buf`.
+    ///
+    /// `buf` must be exactly as long as the answer to `command`.
+    pub(crate) fn read_register(&mut self, command: Instruction, buf: &mut [u8]) -> Result<(), DisplayError<SPI, DC>> {
+        let (len, dummy) = read_format(&command).ok_or(Error::InvalidArgument)?;
+        if buf.len() != len {
+            return Err(Error::InvalidArgument);
+        }
+        self.start_read(command)?;
+        if dummy {
+            self.read_after_dummy_clock(buf)
+        } else {
+            self.read_bytes(buf)
+        }
+    }
+
+    /// Reads the manufacturer, version and module ID bytes (`RDDID`).
+    ///
+    /// An unpowered or missing panel usually answers all zeros or all ones.
+    pub fn read_id(&mut self) -> Result<[u8; 3], DisplayError<SPI, DC>> {
+        let mut id = [0u8; 3];
+        self.read_register(Instruction::RDDID, &mut id)?;
+        Ok(id)
+    }
+
+    /// Reads the four display status bytes (`RDDST`).
+    pub fn read_status(&mut self) -> Result<[u8; 4], DisplayError<SPI, DC>> {
+        let mut status = [0u8; 4];
+        self.read_register(Instruction::RDDST, &mut status)?;
+        Ok(status)
+    }
+
+    /// Reads the power mode byte (`RDDPM`).
+    ///
+    /// From the top bit down it flags booster on, idle mode, partial mode,
+    /// sleep out, normal mode and display on.
+    pub fn read_power_mode(&mut self) -> Result<u8, DisplayError<SPI, DC>> {
+        let mut mode = [0u8; 1];
+        self.read_register(Instruction::RDDPM, &mut mode)?;
+        Ok(mode[0])
+    }
+
+    /// Reads MADCTL back (`RDDMADCTL`); its two lowest bits read as zero.
+    pub fn read_madctl(&mut self) -> Result<u8, DisplayError<SPI, DC>> {
+        let mut madctl = [0u8; 1];
+        self.read_register(Instruction::RDDMADCTL, &mut madctl)?;
+        Ok(madctl[0])
+    }
+
+    /// Reads the frame memory of the given window into `buf`.
+    ///
+    /// The controller answers in 18-bit color regardless of the pixel
+    /// format used for writing: three bytes per pixel, red, green and blue,
+    /// each in the upper six bits (see [`rgb666_to_rgb565`]). `buf` must
+    /// hold three bytes for every pixel of the window, which must lie on
+    /// the panel.
+    ///
+    /// [`rgb666_to_rgb565`]: crate::color::rgb666_to_rgb565
+    pub fn read_ram(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, buf: &mut [u8]) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey || ex >= self.width || ey >= self.height {
+            return Err(Error::OutOfBounds);
+        }
+        let count = 3 * (ex as usize - sx as usize + 1) * (ey as usize - sy as usize + 1);
+        let buf = buf.get_mut(..count).ok_or(Error::InvalidArgument)?;
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.start_read(Instruction::RAMRD)?;
+        self.read_after_dummy_clock(buf)
+    }
+
+    /// Reads the frame memory of the given window as RGB565 pixels.
+    ///
+    /// Like [`ST7735::read_ram`], but converts the answer a chunk at a
+    /// time, so no 18-bit buffer is needed. `buf` must hold a pixel for
+    /// every pixel of the window, which must lie on the panel. Color
+    /// transforms are not undone.
+    pub fn read_pixels(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, buf: &mut [u16]) -> Result<(), DisplayError<SPI, DC>> {
+        if sx > ex || sy > ey || ex >= self.width || ey >= self.height {
+            return Err(Error::OutOfBounds);
+        }
+        let count = (ex as usize - sx as usize + 1) * (ey as usize - sy as usize + 1);
+        let buf = buf.get_mut(..count).ok_or(Error::InvalidArgument)?;
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.start_read(Instruction::RAMRD)?;
+        // raw[0] is the last byte of the previous chunk, still holding the
+        // first bit of the next one after the dummy clock.
+        let mut raw = [0u8; 3 * CHUNK_PIXELS + 1];
+        self.read_bytes(&mut raw[..1])?;
+        for chunk in buf.chunks_mut(CHUNK_PIXELS) {
+            let len = chunk.len() * 3;
+            self.read_bytes(&mut raw[1..=len])?;
+            let byte = |i: usize| (raw[i] << 1) | (raw[i + 1] >> 7);
+            for (i, pixel) in chunk.iter_mut().enumerate() {
+                *pixel = rgb666_to_rgb565(byte(3 * i), byte(3 * i + 1), byte(3 * i + 2));
+            }
+            raw[0] = raw[len];
+        }
+        Ok(())
+    }
+
+    /// Sends `command` and switches the bus to the data phase.
+    fn start_read(&mut self, command: Instruction) -> Result<(), DisplayError<SPI, DC>> {
+        self.finish_pixels()?;
+        self.command_seq = self.command_seq.wrapping_add(1);
+        self.dc.set_low().map_err(Error::Dc)?;
+        self.bus_write(&[command.to_u8().unwrap()], false)?;
+        self.dc.set_high().map_err(Error::Dc)
+    }
+
+    /// Reads `buf.len()` bytes that follow a single dummy clock.
+    fn read_after_dummy_clock(&mut self, buf: &mut [u8]) -> Result<(), DisplayError<SPI, DC>> {
+        self.read_bytes(buf)?;
+        let mut last = [0u8];
+        self.read_bytes(&mut last)?;
+        let mut carry = last[0] >> 7;
+        for byte in buf.iter_mut().rev() {
+            let next = *byte >> 7;
+            *byte = (*byte << 1) | carry;
+            carry = next;
+        }
+        Ok(())
+    }
+
+    /// Clocks zeros out while reading `buf.len()` bytes in, in one transfer.
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), DisplayError<SPI, DC>> {
+        for byte in buf.iter_mut() {
+            *byte = 0;
+        }
+        spi::Transfer::transfer(&mut self.spi, buf).map_err(Error::Spi)?;
+        Ok(())
+    }
+}
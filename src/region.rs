@@ -0,0 +1,432 @@
+//! Split-screen regions with their own origin, clipping and rotation.
+
+use crate::{DisplayError, Error, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A rectangular area of the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Left edge, in panel coordinates.
+    pub x: u16,
+
+    /// Top edge, in panel coordinates.
+    pub y: u16,
+
+    /// Width in pixels.
+    pub width: u16,
+
+    /// Height in pixels.
+    pub height: u16,
+}
+
+impl Region {
+    /// Creates a region with the given top left corner and size.
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Region { x, y, width, height }
+    }
+
+    /// Splits off the top `height` rows, returning `(top, rest)`.
+    pub fn split_top(self, height: u16) -> (Region, Region) {
+        let height = height.min(self.height);
+        (
+            Region::new(self.x, self.y, self.width, height),
+            Region::new(self.x, self.y + height, self.width, self.height - height),
+        )
+    }
+
+    /// Splits off the left `width` columns, returning `(left, rest)`.
+    pub fn split_left(self, width: u16) -> (Region, Region) {
+        let width = width.min(self.width);
+        (
+            Region::new(self.x, self.y, width, self.height),
+            Region::new(self.x + width, self.y, self.width - width, self.height),
+        )
+    }
+
+    /// Returns the panel coords of the bottom right pixel, or `None` if the
+    /// region is empty or reaches past `u16::MAX`.
+    pub fn corner(&self) -> Option<(u16, u16)> {
+        let ex = self.x.checked_add(self.width.checked_sub(1)?)?;
+        let ey = self.y.checked_add(self.height.checked_sub(1)?)?;
+        Some((ex, ey))
+    }
+
+    /// Returns whether the region-local coords lie inside the region.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x < self.width && y < self.height
+    }
+}
+
+/// Rotation of a region's content relative to the panel, clockwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// Longest run of pixels buffered when transposing rotated content.
+const LINE_LEN: usize = 162;
+
+/// A drawing target limited to one region of the display.
+///
+/// Coordinates are relative to the region's top left corner and anything
+/// outside the region is silently clipped, so widgets drawn into a view
+/// never need to know where on the panel they really are.
+pub struct RegionView<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    region: Region,
+    rotation: Rotation,
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Borrows the display as a drawing target for a single region.
+    ///
+    /// A region reaching up to `u16::MAX` is cut off just before it, which
+    /// is far past any panel.
+    pub fn region(&mut self, region: Region) -> RegionView<'_, SPI, DC, RST> {
+        let Region { x, y, width, height } = region;
+        RegionView {
+            display: self,
+            region: Region::new(x, y, width.min(u16::MAX - x), height.min(u16::MAX - y)),
+            rotation: Rotation::Deg0,
+        }
+    }
+}
+
+impl<'a, SPI, DC, RST> RegionView<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Returns the region this view draws into.
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Renders the view's content rotated relative to the panel.
+    ///
+    /// Rotation is done in software while blitting, so the global
+    /// orientation is left alone and other regions are unaffected. With
+    /// `Deg90` or `Deg270` the view is `region.height` wide and
+    /// `region.width` tall.
+    pub fn rotated(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Returns the content rotation of this view.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Returns the `(width, height)` of the view in local coords.
+    pub fn size(&self) -> (u16, u16) {
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (self.region.width, self.region.height),
+            Rotation::Deg90 | Rotation::Deg270 => (self.region.height, self.region.width),
+        }
+    }
+
+    /// Maps region-local coords to panel coords.
+    ///
+    /// The coords must lie inside the view. The sums cannot overflow as
+    /// the region ends before `u16::MAX`.
+    fn to_panel(&self, x: u16, y: u16) -> (u16, u16) {
+        let Region { x: rx, y: ry, width, height } = self.region;
+        match self.rotation {
+            Rotation::Deg0 => (rx + x, ry + y),
+            Rotation::Deg90 => (rx + width - 1 - y, ry + x),
+            Rotation::Deg180 => (rx + width - 1 - x, ry + height - 1 - y),
+            Rotation::Deg270 => (rx + y, ry + height - 1 - x),
+        }
+    }
+
+    /// Returns the underlying display.
+    pub fn display(&mut self) -> &mut ST7735<SPI, DC, RST> {
+        self.display
+    }
+
+    /// Sets a pixel color at the given region-local coords.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let (width, height) = self.size();
+        if x >= width || y >= height {
+            return Ok(());
+        }
+        let (px, py) = self.to_panel(x, y);
+        self.display.set_pixel(px, py, color)
+    }
+
+    /// Sets pixel colors at the given region-local drawing window.
+    ///
+    /// Parts of the window outside the region are dropped from `colors`.
+    pub fn set_pixels<P: IntoIterator<Item = u16>>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: P,
+    ) -> Result<(), DisplayError<SPI, DC>> {
+        let (width, height) = self.size();
+        if sx > ex || sy > ey || sx >= width || sy >= height {
+            return Ok(());
+        }
+        let cex = ex.min(width - 1);
+        let cey = ey.min(height - 1);
+        if self.rotation != Rotation::Deg0 {
+            return self.set_pixels_rotated(sx, sy, ex, cex, cey, colors);
+        }
+        let (rx, ry) = (self.region.x, self.region.y);
+
+        if cex == ex && cey == ey {
+            return self.display.set_pixels(rx + sx, ry + sy, rx + ex, ry + ey, colors);
+        }
+
+        let width = ex as usize - sx as usize + 1;
+        let visible = colors.into_iter().enumerate().filter_map(|(i, color)| {
+            let x = sx as usize + i % width;
+            let y = sy as usize + i / width;
+            if x <= cex as usize && y <= cey as usize {
+                Some(color)
+            } else {
+                None
+            }
+        });
+        self.display.set_pixels(rx + sx, ry + sy, rx + cex, ry + cey, visible)
+    }
+
+    /// Fills the whole view from `colors`, row-major in local coords.
+    ///
+    /// The view is sent as a single window with one write, whatever the
+    /// rotation, and clipped to the panel like [`RegionView::set_pixels`].
+    /// Fails without drawing anything unless `colors` holds exactly one
+    /// pixel per pixel of the view.
+    pub fn flush(&mut self, colors: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        let Region { x, y, width, height } = self.region;
+        if colors.len() != width as usize * height as usize {
+            return Err(Error::InvalidArgument);
+        }
+        if colors.is_empty() {
+            return Ok(());
+        }
+        if self.rotation == Rotation::Deg0 {
+            return self.display.flush_region(self.region, colors);
+        }
+        let (w, h) = (width as usize, height as usize);
+        let rotation = self.rotation;
+        // Walk the region in panel order and pick the matching local pixel.
+        let panel_order = (0..colors.len()).map(move |i| {
+            let (u, v) = (i % w, i / w);
+            let local = match rotation {
+                Rotation::Deg0 => v * w + u,
+                Rotation::Deg90 => (w - 1 - u) * h + v,
+                Rotation::Deg180 => (h - 1 - v) * w + (w - 1 - u),
+                Rotation::Deg270 => u * h + (h - 1 - v),
+            };
+            colors[local]
+        });
+        self.display.set_pixels(x, y, x + width - 1, y + height - 1, panel_order)
+    }
+
+    /// Streams a rotated window one local row at a time.
+    ///
+    /// Each local row lands on a single panel row or column, so it can be
+    /// sent as one window once it has been put into panel order.
+    fn set_pixels_rotated<P: IntoIterator<Item = u16>>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        cex: u16,
+        cey: u16,
+        colors: P,
+    ) -> Result<(), DisplayError<SPI, DC>> {
+        let mut line = [0u16; LINE_LEN];
+        let mut len = 0;
+        let mut start = sx;
+        let (mut x, mut y) = (sx, sy);
+        for color in colors {
+            if x <= cex {
+                line[len] = color;
+                len += 1;
+                if len == LINE_LEN {
+                    self.write_line(start, y, &line[..len])?;
+                    start += len as u16;
+                    len = 0;
+                }
+            }
+            if x == ex {
+                if len > 0 {
+                    self.write_line(start, y, &line[..len])?;
+                }
+                if y == cey {
+                    return Ok(());
+                }
+                len = 0;
+                start = sx;
+                x = sx;
+                y += 1;
+            } else {
+                x += 1;
+            }
+        }
+        if len > 0 {
+            self.write_line(start, y, &line[..len])?;
+        }
+        Ok(())
+    }
+
+    /// Writes a run of pixels starting at local `(x, y)` along a local row.
+    fn write_line(&mut self, x: u16, y: u16, pixels: &[u16]) -> Result<(), DisplayError<SPI, DC>> {
+        let last = x + pixels.len() as u16 - 1;
+        let (x0, y0) = self.to_panel(x, y);
+        let (x1, y1) = self.to_panel(last, y);
+        let forward = pixels.iter().copied();
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg90 => self.display.set_pixels(x0, y0, x1, y1, forward),
+            Rotation::Deg180 | Rotation::Deg270 => self.display.set_pixels(x1, y1, x0, y0, forward.rev()),
+        }
+    }
+
+    /// Fills the whole region with a single color.
+    pub fn clear(&mut self, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let Region { x, y, width, height } = self.region;
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        self.display.fill_window(x, y, x + width - 1, y + height - 1, color)
+    }
+}
+
+#[cfg(feature = "graphics")]
+use crate::{area_window, point_coords};
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> OriginDimensions for RegionView<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        let (width, height) = RegionView::size(self);
+        Size::new(width as u32, height as u32)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST> DrawTarget for RegionView<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = DisplayError<SPI, DC>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some((x, y)) = point_coords(point) {
+                self.set_pixel(x, y, color.into_storage())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        match area_window(area) {
+            Some((sx, sy, ex, ey)) => {
+                let count = area.size.width as usize * area.size.height as usize;
+                let colors = colors.into_iter().take(count).map(|color| color.into_storage());
+                self.set_pixels(sx, sy, ex, ey, colors)
+            }
+            None => self.draw_iter(area.points().zip(colors).map(|(point, color)| Pixel(point, color))),
+        }
+    }
+}
+
+#[cfg(feature = "graphics-legacy")]
+mod legacy {
+    use super::*;
+
+    use embedded_graphics_legacy::{drawable::{Pixel, Dimensions}, pixelcolor::Rgb565, Drawing, SizedDrawing};
+
+    impl<'a, SPI, DC, RST> Drawing<Rgb565> for RegionView<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(coord, color) in item_pixels {
+                if coord.0 > u16::MAX as u32 || coord.1 > u16::MAX as u32 {
+                    continue;
+                }
+                if self.set_pixel(coord.0 as u16, coord.1 as u16, color.0).is_err() {
+                    panic!("pixel write failed");
+                }
+            }
+        }
+    }
+
+    impl<'a, SPI, DC, RST> SizedDrawing<Rgb565> for RegionView<'a, SPI, DC, RST>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        fn draw_sized<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>> + Dimensions,
+        {
+            let top_left = item_pixels.top_left();
+            let bottom_right = item_pixels.bottom_right();
+            // Items reaching past the top or left edge, or out of `u16`
+            // range, are clipped pixel by pixel instead of truncating the
+            // window coords.
+            let max = u16::MAX as i32;
+            if top_left.0 < 0 || top_left.1 < 0 || bottom_right.0 > max || bottom_right.1 > max {
+                return self.draw(item_pixels);
+            }
+
+            let result = self.set_pixels(top_left.0 as u16, top_left.1 as u16,
+                            bottom_right.0 as u16, bottom_right.1 as u16,
+                            item_pixels.into_iter().map(|Pixel(_coord, color)| color.0));
+            if result.is_err() {
+                panic!("pixels write failed");
+            }
+        }
+    }
+}
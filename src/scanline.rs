@@ -0,0 +1,196 @@
+//! Reordering of scattered pixels into scanline order.
+
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Longest run sent as a single window.
+const RUN_LEN: usize = 64;
+
+#[derive(Clone, Copy, Default)]
+struct Entry {
+    y: u16,
+    x: u16,
+    seq: u16,
+    color: u16,
+}
+
+/// Buffers up to `N` pixels and sends them sorted by row and column.
+///
+/// Primitives such as thick diagonal lines and arcs produce their pixels in
+/// an order that needs a new window for nearly every pixel. Sorting a batch
+/// first lets horizontally adjacent pixels share one window. Pixels drawn
+/// twice in a batch keep the color drawn last.
+///
+/// Pending pixels are only sent once the buffer fills up or
+/// [`ScanlineSorter::flush`] is called; dropping the sorter discards them.
+pub struct ScanlineSorter<'a, SPI, DC, RST, const N: usize>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    entries: [Entry; N],
+    len: usize,
+}
+
+impl<'a, SPI, DC, RST, const N: usize> ScanlineSorter<'a, SPI, DC, RST, N>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates an empty sorter drawing to `display`.
+    pub fn new(display: &'a mut ST7735<SPI, DC, RST>) -> Self {
+        ScanlineSorter {
+            display,
+            entries: [Entry::default(); N],
+            len: 0,
+        }
+    }
+
+    /// Queues a pixel, sending the batch first if the buffer is full.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        if self.len == N {
+            self.flush()?;
+        }
+        if N == 0 {
+            return self.display.set_pixel(x, y, color);
+        }
+        self.entries[self.len] = Entry {
+            y,
+            x,
+            seq: self.len as u16,
+            color,
+        };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Sends all pending pixels in scanline order.
+    pub fn flush(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        let entries = &mut self.entries[..self.len];
+        self.len = 0;
+        entries.sort_unstable_by_key(|e| (e.y, e.x, e.seq));
+
+        let mut run = [0u16; RUN_LEN];
+        let mut run_len = 0;
+        let (mut run_x, mut run_y) = (0, 0);
+        for e in entries.iter() {
+            if run_len > 0 && e.y == run_y && e.x == run_x + run_len as u16 - 1 {
+                // Same pixel again, the later one wins.
+                run[run_len - 1] = e.color;
+                continue;
+            }
+            let extends = run_len > 0 && run_len < RUN_LEN && e.y == run_y && e.x == run_x + run_len as u16;
+            if !extends {
+                if run_len > 0 {
+                    send_run(self.display, run_x, run_y, &run[..run_len])?;
+                }
+                run_x = e.x;
+                run_y = e.y;
+                run_len = 0;
+            }
+            run[run_len] = e.color;
+            run_len += 1;
+        }
+        if run_len > 0 {
+            send_run(self.display, run_x, run_y, &run[..run_len])?;
+        }
+        Ok(())
+    }
+}
+
+fn send_run<SPI, DC, RST>(display: &mut ST7735<SPI, DC, RST>, x: u16, y: u16, colors: &[u16]) -> Result<(), DisplayError<SPI, DC>>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    let ex = x + colors.len() as u16 - 1;
+    display.set_pixels(x, y, ex, y, colors.iter().copied())
+}
+
+#[cfg(feature = "graphics")]
+use crate::point_coords;
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    Pixel,
+};
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST, const N: usize> OriginDimensions for ScanlineSorter<'a, SPI, DC, RST, N>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        let (width, height) = self.display.size();
+        Size::new(width as u32, height as u32)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<'a, SPI, DC, RST, const N: usize> DrawTarget for ScanlineSorter<'a, SPI, DC, RST, N>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = DisplayError<SPI, DC>;
+
+    /// Draws pixels in sorted batches of up to `N`, flushing the last
+    /// batch before returning.
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some((x, y)) = point_coords(point) {
+                self.set_pixel(x, y, color.into_storage())?;
+            }
+        }
+        self.flush()
+    }
+}
+
+#[cfg(feature = "graphics-legacy")]
+mod legacy {
+    use super::*;
+
+    use embedded_graphics_legacy::{drawable::Pixel, pixelcolor::Rgb565, Drawing};
+
+    impl<'a, SPI, DC, RST, const N: usize> Drawing<Rgb565> for ScanlineSorter<'a, SPI, DC, RST, N>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        /// Draws an item, sending it in sorted batches of up to `N` pixels.
+        ///
+        /// The last batch is flushed before returning.
+        fn draw<T>(&mut self, item_pixels: T)
+        where
+            T: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(coord, color) in item_pixels {
+                if coord.0 > u16::MAX as u32 || coord.1 > u16::MAX as u32 {
+                    continue;
+                }
+                if self.set_pixel(coord.0 as u16, coord.1 as u16, color.0).is_err() {
+                    panic!("pixel write failed");
+                }
+            }
+            if self.flush().is_err() {
+                panic!("pixel write failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Rolling waveform view drawn one column per sample.
+
+use crate::region::Region;
+use crate::spectrum::ColumnStreamer;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// An oscilloscope-style trace.
+///
+/// Every sample replaces one whole column, erasing what was there and
+/// drawing the segment from the previous sample, so a sample costs a
+/// single column window regardless of what is on screen.
+#[derive(Clone, Copy, Debug)]
+pub struct Scope {
+    region: Region,
+    trace: u16,
+    background: u16,
+    /// Column the next sample goes to, when sweeping
+    x: u16,
+    last: Option<u16>,
+    roll: Option<ColumnStreamer>,
+}
+
+impl Scope {
+    /// Creates a sweeping trace inside `region`.
+    ///
+    /// Samples are drawn left to right and wrap around to overwrite the
+    /// oldest ones, like an analog scope.
+    pub const fn new(region: Region, trace: u16, background: u16) -> Self {
+        Scope {
+            region,
+            trace,
+            background,
+            x: 0,
+            last: None,
+            roll: None,
+        }
+    }
+
+    /// Creates a rolling trace across the whole panel.
+    ///
+    /// New samples appear at the right edge while older ones move left,
+    /// which uses hardware scroll and has its requirements, see
+    /// [`ColumnStreamer`].
+    pub fn rolling<SPI, DC, RST>(display: &mut ST7735<SPI, DC, RST>, trace: u16, background: u16) -> Result<Self, DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let roll = ColumnStreamer::new(display)?;
+        let (width, height) = display.size();
+        let mut scope = Scope::new(Region::new(0, 0, width, height), trace, background);
+        scope.roll = Some(roll);
+        Ok(scope)
+    }
+
+    /// Draws the next sample, `y` rows from the top of the view.
+    ///
+    /// Samples below the view are drawn on its bottom row.
+    pub fn push_sample<SPI, DC, RST>(&mut self, display: &mut ST7735<SPI, DC, RST>, y: u16) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let Region { x, y: top, width, height } = self.region;
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let y = y.min(height - 1);
+        let (from, to) = match self.last {
+            Some(last) => (last.min(y), last.max(y)),
+            None => (y, y),
+        };
+        let (trace, background) = (self.trace, self.background);
+        let column = (0..height).map(|row| if (from..=to).contains(&row) { trace } else { background });
+
+        match self.roll.as_mut() {
+            Some(roll) => roll.push_column(display, column)?,
+            None => {
+                let cx = x + self.x;
+                display.set_pixels(cx, top, cx, top + height - 1, column)?;
+                self.x = (self.x + 1) % width;
+            }
+        }
+        // A sweep starts a fresh segment at the left edge
+        self.last = if self.roll.is_none() && self.x == 0 { None } else { Some(y) };
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! Hardware vertical scrolling.
+//!
+//! Scrolling works on lines of the controller's frame memory, which in
+//! the default orientation run top to bottom. Moving the scroll offset
+//! changes which line is shown first in the scrolling area without
+//! resending any pixels, so terminals and tickers only draw the newly
+//! exposed line.
+
+use crate::instruction::Instruction;
+use crate::region::Region;
+use crate::{DisplayError, Error, MadctlFlags, Orientation, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Number of lines in the controller's frame memory.
+pub const FRAME_LINES: u16 = 162;
+
+/// Number of pixels per line of the controller's frame memory.
+pub const FRAME_COLUMNS: u16 = 132;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Splits the frame memory lines into a fixed top, scrolling middle and
+    /// fixed bottom part. The three must add up to [`FRAME_LINES`].
+    ///
+    /// Panels smaller than the frame memory usually sit at an offset, which
+    /// has to be counted into `top_fixed` and `bottom_fixed`.
+    pub fn set_scroll_area(&mut self, top_fixed: u16, scroll_height: u16, bottom_fixed: u16) -> Result<(), DisplayError<SPI, DC>> {
+        if top_fixed as u32 + scroll_height as u32 + bottom_fixed as u32 != FRAME_LINES as u32 {
+            return Err(Error::InvalidArgument);
+        }
+        self.write_command(Instruction::VSCRDEF, None)?;
+        self.write_word(top_fixed)?;
+        self.write_word(scroll_height)?;
+        self.write_word(bottom_fixed)
+    }
+
+    /// Sets the frame memory line shown first in the scrolling area.
+    ///
+    /// `line` counts from the top of frame memory, so the unscrolled
+    /// position is `top_fixed`.
+    pub fn set_scroll_offset(&mut self, line: u16) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::VSCSAD, None)?;
+        self.write_word(line)
+    }
+
+    /// Returns whether hardware scrolling of the top `height` rows of
+    /// `region` moves exactly those rows.
+    ///
+    /// That needs them to be whole frame memory lines in top to bottom
+    /// order: the region spans the full panel width in portrait, without
+    /// vertical mirroring.
+    pub(crate) fn can_scroll_rows(&self, region: Region, height: u16) -> bool {
+        let flags = self.flags;
+        self.orientation == Orientation::Portrait
+            && !flags.contains(MadctlFlags::MY)
+            && !flags.contains(MadctlFlags::MV)
+            && region.x == 0
+            && region.width == self.width
+            && height > 0
+            && self.dy as u32 + region.y as u32 + height as u32 <= FRAME_LINES as u32
+    }
+}
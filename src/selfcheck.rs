@@ -0,0 +1,162 @@
+//! Bring-up check of the panel wiring.
+
+use crate::instruction::Instruction;
+use crate::scroll::{FRAME_COLUMNS, FRAME_LINES};
+use crate::variant::Variant;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A wiring problem found by the self-check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WiringFault {
+    /// The SPI bus or a pin reported an error.
+    Bus,
+
+    /// Every byte read back was `0x00` or `0xFF`.
+    NoResponse,
+
+    /// The controller answered, but MADCTL read back differently from
+    /// what was written.
+    MadctlMismatch { written: u8, read: u8 },
+}
+
+impl WiringFault {
+    /// Returns a short hint on what to check.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            WiringFault::Bus => "bus error (check the SPI and pin configuration)",
+            WiringFault::NoResponse => "no response (check CS/SCK, MISO/SDA and power)",
+            WiringFault::MadctlMismatch { .. } => "ID plausible but MADCTL readback mismatched (check DC)",
+        }
+    }
+}
+
+/// What [`ST7735::probe`] found out about a panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PanelReport {
+    /// Manufacturer, driver version and driver ID (RDID1 to RDID3).
+    pub id: [u8; 3],
+
+    /// Whether pixels written to frame memory read back, i.e. MISO is wired.
+    pub readback: bool,
+
+    /// Recommended `rgb` argument of [`ST7735::new`].
+    pub rgb: bool,
+
+    /// Recommended offset for [`ST7735::set_offset`] in the current
+    /// orientation.
+    ///
+    /// Taken from the [`Variant`] the driver was created for, or else the
+    /// one of the panel's size. Panels of other sizes are assumed centered
+    /// in frame memory.
+    pub offset: (u16, u16),
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8> + spi::Transfer<u8, Error = <SPI as spi::Write<u8>>::Error>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    /// Runs [`ST7735::init`] followed by [`ST7735::self_check`].
+    pub fn init_checked<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), WiringFault>
+        where DELAY: DelayMs<u8>
+    {
+        self.init(delay).map_err(|_| WiringFault::Bus)?;
+        self.self_check()
+    }
+
+    /// Checks that the panel answers reads and executes commands.
+    ///
+    /// The ID registers must return something other than an idle bus, and
+    /// MADCTL must read back as last written, which fails when DC
+    /// is miswired because the write was then taken as data.
+    pub fn self_check(&mut self) -> Result<(), WiringFault> {
+        let mut id = [0u8; 3];
+        for (byte, command) in id.iter_mut().zip([Instruction::RDID1, Instruction::RDID2, Instruction::RDID3]) {
+            let mut buf = [0u8; 1];
+            self.read_register(command, &mut buf).map_err(|_| WiringFault::Bus)?;
+            *byte = buf[0];
+        }
+        if id.iter().all(|&b| b == 0x00) || id.iter().all(|&b| b == 0xFF) {
+            return Err(WiringFault::NoResponse);
+        }
+
+        let mut madctl = [0u8; 1];
+        self.read_register(Instruction::RDDMADCTL, &mut madctl).map_err(|_| WiringFault::Bus)?;
+        // The two lowest bits are not readable.
+        if madctl[0] & 0xFC != self.madctl & 0xFC {
+            return Err(WiringFault::MadctlMismatch {
+                written: self.madctl,
+                read: madctl[0],
+            });
+        }
+        Ok(())
+    }
+
+    /// Initializes the panel and works out settings for it.
+    ///
+    /// Reads the ID registers, then writes a red and a green pixel at the
+    /// origin and reads them back to find out whether frame memory can be
+    /// read and whether red and blue are swapped on the way in. The pixel
+    /// is cleared to black afterwards.
+    ///
+    /// Inversion is applied between frame memory and the glass, so it
+    /// cannot be read back and has to be judged by eye.
+    pub fn probe<DELAY>(&mut self, delay: &mut DELAY) -> Result<PanelReport, DisplayError<SPI, DC>>
+        where DELAY: DelayMs<u8>
+    {
+        self.init(delay)?;
+
+        let mut id = [0u8; 3];
+        for (byte, command) in id.iter_mut().zip([Instruction::RDID1, Instruction::RDID2, Instruction::RDID3]) {
+            let mut buf = [0u8; 1];
+            self.read_register(command, &mut buf)?;
+            *byte = buf[0];
+        }
+
+        let red = self.probe_pixel(0xF800)?;
+        let green = self.probe_pixel(0x07E0)?;
+        self.probe_pixel(0x0000)?;
+        let green_ok = green[1] & 0xFC == 0xFC && green[0] & 0xFC == 0 && green[2] & 0xFC == 0;
+        let straight = red[0] & 0xF8 == 0xF8 && red[2] & 0xF8 == 0;
+        let swapped = red[2] & 0xF8 == 0xF8 && red[0] & 0xF8 == 0;
+        let readback = green_ok && (straight || swapped);
+
+        let (width, height) = self.portrait_size();
+        let (dx, dy, gram_width, gram_height) = match self.variant.or_else(|| Variant::of_size(width, height)) {
+            Some(variant) => {
+                let (dx, dy) = variant.offset();
+                let (gram_width, gram_height) = variant.gram_size();
+                (dx, dy, gram_width, gram_height)
+            }
+            None => (
+                FRAME_COLUMNS.saturating_sub(width) / 2,
+                FRAME_LINES.saturating_sub(height) / 2,
+                FRAME_COLUMNS,
+                FRAME_LINES,
+            ),
+        };
+        Ok(PanelReport {
+            id,
+            readback,
+            rgb: if readback && swapped { !self.rgb } else { self.rgb },
+            offset: self.gram_offsets(dx, dy, gram_width, gram_height)[self.orientation.index()],
+        })
+    }
+
+    /// Writes one raw pixel at the origin and reads it back.
+    fn probe_pixel(&mut self, color: u16) -> Result<[u8; 3], DisplayError<SPI, DC>> {
+        self.set_address_window(0, 0, 0, 0)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        self.write_encoded(&[color])?;
+        self.finish_pixels()?;
+        let mut read = [0u8; 3];
+        self.read_ram(0, 0, 0, 0, &mut read)?;
+        Ok(read)
+    }
+}
@@ -0,0 +1,120 @@
+//! Initialization in steps, for callers that cannot block for its delays.
+
+use crate::instruction::Instruction;
+use crate::model::{self, Model};
+use crate::{DisplayError, InitCommand, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Time the controller needs after a reset, after leaving sleep and
+/// after switching the display on.
+const SETTLE_MS: u8 = 200;
+
+/// The steps of initializing the display, as an iterator.
+///
+/// Each call to `next` sends the commands of one step and yields how many
+/// milliseconds to wait before the next one, so the roughly 600 ms of
+/// delays can be spent on other work or left to a timer. The sequence is
+/// the one [`ST7735::init_model`] runs and ends after the first error.
+///
+/// ```ignore
+/// let mut steps = display.init_sequence();
+/// while let Some(wait_ms) = steps.next() {
+///     timer.start(wait_ms?);
+///     // ...other work until the timer expires...
+/// }
+/// ```
+pub struct InitSequence<'a, 'c, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    model: &'c [InitCommand<'c>],
+    commands: &'c [InitCommand<'c>],
+    /// Next step; `None` once done or failed
+    step: Option<usize>,
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    /// Returns the steps of [`ST7735::init`].
+    pub fn init_sequence(&mut self) -> InitSequence<'_, 'static, SPI, DC, RST> {
+        self.init_sequence_with(&model::ST7735R, &[])
+    }
+
+    /// Returns the steps of [`ST7735::init_model`].
+    pub fn init_sequence_with<'c, M>(&mut self, model: &'c M, commands: &'c [InitCommand<'c>]) -> InitSequence<'_, 'c, SPI, DC, RST>
+    where
+        M: Model + ?Sized,
+    {
+        InitSequence {
+            display: self,
+            model: model.init_commands(),
+            commands,
+            step: Some(0),
+        }
+    }
+}
+
+impl<'a, 'c, SPI, DC, RST> InitSequence<'a, 'c, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    /// Sends step `step` and returns the delay after it, or `None` past
+    /// the last one.
+    fn run(&mut self, step: usize) -> Option<Result<u8, DisplayError<SPI, DC>>> {
+        let display = &mut *self.display;
+        let model_end = 2 + self.model.len();
+        let result = match step {
+            0 => display
+                .hard_reset()
+                .and_then(|_| display.write_command(Instruction::SWRESET, None))
+                .map(|_| SETTLE_MS),
+            1 => display.write_command(Instruction::SLPOUT, None).map(|_| SETTLE_MS),
+            _ if step < model_end => send(display, &self.model[step - 2]),
+            _ if step == model_end => display.send_settings().map(|_| SETTLE_MS),
+            _ => send(display, self.commands.get(step - model_end - 1)?),
+        };
+        Some(result)
+    }
+}
+
+/// Sends an init command and returns its delay.
+fn send<SPI, DC, RST>(display: &mut ST7735<SPI, DC, RST>, command: &InitCommand) -> Result<u8, DisplayError<SPI, DC>>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    let params = if command.params.is_empty() { None } else { Some(command.params) };
+    display.write_command(command.instr, params)?;
+    Ok(command.delay_ms)
+}
+
+impl<'a, 'c, SPI, DC, RST> Iterator for InitSequence<'a, 'c, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin<Error = <DC as OutputPin>::Error>,
+{
+    type Item = Result<u8, DisplayError<SPI, DC>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = self.step?;
+        let result = self.run(step);
+        self.step = match result {
+            Some(Ok(_)) => Some(step + 1),
+            _ => None,
+        };
+        result
+    }
+}
@@ -0,0 +1,132 @@
+//! Sharing the driver between the main loop and interrupt handlers.
+
+use crate::region::Region;
+use crate::{DisplayError, Error, ST7735};
+
+use core::cell::{RefCell, UnsafeCell};
+
+use critical_section::Mutex;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// A rectangle of pixels waiting to be drawn.
+type Blit = (Region, &'static [u16]);
+
+struct State<const N: usize> {
+    busy: bool,
+    pending: [Option<Blit>; N],
+}
+
+/// A driver usable from both the main loop and interrupt handlers.
+///
+/// Critical sections are only held for a few instructions to claim or
+/// release the driver, never while drawing, so interrupts stay enabled
+/// during transfers.
+///
+/// Nothing here ever waits: [`SharedDisplay::with`] returns `None` while
+/// the driver is in use, and [`SharedDisplay::blit`] from an interrupt that
+/// finds the driver busy queues the blit in one of `N` slots. Queued blits
+/// are drawn, in order, by whoever releases the driver next.
+pub struct SharedDisplay<SPI, DC, RST, const N: usize>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: UnsafeCell<ST7735<SPI, DC, RST>>,
+    state: Mutex<RefCell<State<N>>>,
+}
+
+// The display is only reached by whoever set `busy` inside a critical section.
+unsafe impl<SPI, DC, RST, const N: usize> Sync for SharedDisplay<SPI, DC, RST, N>
+where
+    SPI: spi::Write<u8> + Send,
+    DC: OutputPin + Send,
+    RST: OutputPin + Send,
+{
+}
+
+impl<SPI, DC, RST, const N: usize> SharedDisplay<SPI, DC, RST, N>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Wraps a driver for shared use.
+    pub fn new(display: ST7735<SPI, DC, RST>) -> Self {
+        SharedDisplay {
+            display: UnsafeCell::new(display),
+            state: Mutex::new(RefCell::new(State {
+                busy: false,
+                pending: [None; N],
+            })),
+        }
+    }
+
+    /// Runs `f` with the driver, or returns `None` at once if it is in use.
+    pub fn with<R>(&self, f: impl FnOnce(&mut ST7735<SPI, DC, RST>) -> R) -> Option<R> {
+        if !self.claim() {
+            return None;
+        }
+        // Claimed, so nothing else touches the display until released.
+        let result = f(unsafe { &mut *self.display.get() });
+        self.release();
+        Some(result)
+    }
+
+    /// Draws a rectangle of row-major pixels now, or queues it if the
+    /// driver is in use.
+    ///
+    /// Meant for small updates like status icons from interrupt handlers.
+    /// Fails with [`Error::Busy`] if the queue is full or, when drawn now,
+    /// if drawing fails; errors of queued blits are dropped.
+    pub fn blit(&self, rect: Region, pixels: &'static [u16]) -> Result<(), DisplayError<SPI, DC>> {
+        let queued = critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            if !state.busy {
+                state.busy = true;
+                return Ok(false);
+            }
+            let slot = state.pending.iter_mut().find(|slot| slot.is_none()).ok_or(Error::Busy)?;
+            *slot = Some((rect, pixels));
+            Ok(true)
+        })?;
+        if queued {
+            return Ok(());
+        }
+        let result = unsafe { &mut *self.display.get() }.flush_region(rect, pixels);
+        self.release();
+        result
+    }
+
+    /// Tries to mark the driver as in use.
+    fn claim(&self) -> bool {
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            !core::mem::replace(&mut state.busy, true)
+        })
+    }
+
+    /// Draws queued blits, then marks the driver as free.
+    fn release(&self) {
+        loop {
+            let next = critical_section::with(|cs| {
+                let mut state = self.state.borrow_ref_mut(cs);
+                let next = state.pending.first_mut().and_then(Option::take);
+                if next.is_none() {
+                    state.busy = false;
+                } else {
+                    state.pending.rotate_left(1);
+                }
+                next
+            });
+            match next {
+                Some((rect, pixels)) => {
+                    let _ = unsafe { &mut *self.display.get() }.flush_region(rect, pixels);
+                }
+                None => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Streaming column plots (spectrograms, heatmaps) using hardware scroll.
+
+use crate::instruction::Instruction;
+use crate::scroll::FRAME_LINES;
+use crate::{DisplayError, Error, Orientation, ST7735};
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// MADCTL bits selecting the orientation.
+const ORIENTATION_MASK: u8 = 0xE0;
+
+/// Appends one column at a time at the right edge of the panel while older
+/// columns move left.
+///
+/// Instead of resending the plot, each new column overwrites the oldest one
+/// in frame memory and the hardware scroll start moves on by one line, so a
+/// push costs one column of pixels plus a short command.
+///
+/// In [`Orientation::Landscape`] the controller's scroll axis runs along
+/// the panel width, so the streamer needs that orientation and spans the
+/// full panel. Clear the panel first; until the first full pass the old
+/// contents scroll by.
+#[derive(Clone, Copy, Debug)]
+pub struct ColumnStreamer {
+    width: u16,
+    height: u16,
+    /// Panel column holding the oldest data, which is shown at the left edge
+    head: u16,
+}
+
+impl ColumnStreamer {
+    /// Sets up hardware scrolling for a streamer across the whole panel.
+    ///
+    /// Fails without sending anything unless the display is in
+    /// [`Orientation::Landscape`] and its width fits in frame memory.
+    pub fn new<SPI, DC, RST>(display: &mut ST7735<SPI, DC, RST>) -> Result<Self, DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let (width, height) = display.size();
+        let landscape = Orientation::Landscape.to_u8().unwrap();
+        if display.madctl & ORIENTATION_MASK != landscape || width == 0 || height == 0 || display.dx + width > FRAME_LINES {
+            return Err(Error::InvalidArgument);
+        }
+        let top_fixed = display.dx;
+        display.set_scroll_area(top_fixed, width, FRAME_LINES - top_fixed - width)?;
+        display.set_scroll_offset(top_fixed)?;
+        Ok(ColumnStreamer { width, height, head: 0 })
+    }
+
+    /// Appends a column of `height` colors, top to bottom.
+    pub fn push_column<SPI, DC, RST, P>(&mut self, display: &mut ST7735<SPI, DC, RST>, colors: P) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+        P: IntoIterator<Item = u16>,
+    {
+        display.set_pixels(self.head, 0, self.head, self.height - 1, colors)?;
+        self.head = (self.head + 1) % self.width;
+        display.set_scroll_offset(display.dx + self.head)
+    }
+
+    /// Appends a column of levels, e.g. FFT bins, colored through `palette`.
+    ///
+    /// `levels[0]` is drawn at the bottom. The bins are stretched or
+    /// squeezed to the panel height, and levels past the end of the palette
+    /// use its last color.
+    pub fn push_levels<SPI, DC, RST>(
+        &mut self,
+        display: &mut ST7735<SPI, DC, RST>,
+        levels: &[u8],
+        palette: &[u16],
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if levels.is_empty() || palette.is_empty() {
+            return Ok(());
+        }
+        let height = self.height as usize;
+        let colors = (0..height).rev().map(|row| {
+            let level = levels[row * levels.len() / height] as usize;
+            palette[level.min(palette.len() - 1)]
+        });
+        self.push_column(display, colors)
+    }
+
+    /// Stops scrolling and returns the panel to its normal memory mapping.
+    ///
+    /// The plot stays in frame memory but its columns appear rotated, so
+    /// redraw the panel afterwards.
+    pub fn stop<SPI, DC, RST>(self, display: &mut ST7735<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        display.set_scroll_area(0, FRAME_LINES, 0)?;
+        display.set_scroll_offset(0)?;
+        display.write_command(Instruction::NORON, None)
+    }
+
+    /// Returns the panel column the next push writes to.
+    pub fn head(&self) -> u16 {
+        self.head
+    }
+}
@@ -0,0 +1,119 @@
+//! Fast drawing of `embedded-sprites` sprites.
+
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565};
+use embedded_graphics_core::{Drawable, Pixel};
+use embedded_sprites::sprite::Sprite;
+
+/// Longest run of pixels sent in a single window.
+const RUN_LEN: usize = 64;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws a sprite, clipped to the panel.
+    ///
+    /// Sprites hand out their opaque pixels row by row, so consecutive
+    /// pixels are gathered into horizontal runs and each run is sent as
+    /// one window. Transparent pixels end a run and are skipped without
+    /// touching the panel.
+    pub fn draw_sprite(&mut self, sprite: &Sprite<Rgb565>) -> Result<(), DisplayError<SPI, DC>> {
+        let mut runs = Runs {
+            display: self,
+            run: [0; RUN_LEN],
+            len: 0,
+            start: (0, 0),
+        };
+        sprite.draw(&mut runs)?;
+        runs.flush()
+    }
+}
+
+/// Collects a row-major pixel stream into horizontal runs.
+struct Runs<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    run: [u16; RUN_LEN],
+    len: usize,
+    /// Panel coords of the first pixel of the run
+    start: (u16, u16),
+}
+
+impl<'a, SPI, DC, RST> Runs<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn push(&mut self, x: u16, y: u16, color: u16) -> Result<(), DisplayError<SPI, DC>> {
+        let (sx, sy) = self.start;
+        let continues = self.len > 0 && y == sy && x as usize == sx as usize + self.len;
+        if !continues || self.len == RUN_LEN {
+            self.flush()?;
+            self.start = (x, y);
+        }
+        self.run[self.len] = color;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), DisplayError<SPI, DC>> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let (sx, sy) = self.start;
+        let ex = sx + self.len as u16 - 1;
+        let len = self.len;
+        self.len = 0;
+        self.display.set_pixels(sx, sy, ex, sy, self.run[..len].iter().copied())
+    }
+}
+
+impl<'a, SPI, DC, RST> OriginDimensions for Runs<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        let (width, height) = self.display.size();
+        Size::new(width as u32, height as u32)
+    }
+}
+
+impl<'a, SPI, DC, RST> DrawTarget for Runs<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = DisplayError<SPI, DC>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), DisplayError<SPI, DC>>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let (width, height) = self.display.size();
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 || point.x >= width as i32 || point.y >= height as i32 {
+                continue;
+            }
+            self.push(point.x as u16, point.y as u16, color.into_storage())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Ping-pong strip buffers for overlapping pixel preparation with DMA.
+
+use crate::encode;
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, PixelFormat, CHUNK_PIXELS, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Sends strips of encoded pixel data to the panel, usually by DMA.
+///
+/// The driver selects the drawing window and raises DC before the first
+/// strip, so an implementation only has to clock the bytes out.
+pub trait StripSink {
+    type Error;
+
+    /// Starts sending `bytes`.
+    ///
+    /// This may return as soon as the transfer is running. The driver will
+    /// not touch the buffer behind `bytes` again until `wait` has returned,
+    /// so a DMA implementation may keep reading from it until then.
+    fn start(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Blocks until the transfer started last has completed.
+    fn wait(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Two staging buffers used alternately, one being filled while the
+/// other is in flight.
+///
+/// They hold encoded bytes, two per pixel, so a strip of `n` pixels needs
+/// `2 * n` bytes.
+pub struct StripBuffers<'a> {
+    buffers: [&'a mut [u8]; 2],
+}
+
+impl<'a> StripBuffers<'a> {
+    /// Creates strip buffers from two byte slices, ideally of equal length.
+    pub fn new(front: &'a mut [u8], back: &'a mut [u8]) -> Self {
+        StripBuffers {
+            buffers: [front, back],
+        }
+    }
+
+    /// Returns whether both strips hold at least one pixel.
+    pub(crate) fn hold_a_pixel(&self) -> bool {
+        self.buffers.iter().all(|buffer| buffer.len() >= 2)
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Streams the window `(sx, sy)..=(ex, ey)` in strips through `sink`.
+    ///
+    /// `fill` is called with the index of the next pixel and a buffer of up
+    /// to a few dozen pixels to render into, and returns how many pixels it
+    /// produced (zero ends the transfer early). The pixels are encoded into
+    /// one strip while the other is still being sent, so with a DMA sink
+    /// colour conversion and composition overlap with bus time. Sink errors
+    /// are reported as bus errors.
+    ///
+    /// Strips are encoded as RGB565, so other pixel formats fail with
+    /// [`Error::InvalidArgument`], as do strips shorter than one pixel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_strips<S, F>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        strips: &mut StripBuffers,
+        sink: &mut S,
+        fill: F,
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        S: StripSink<Error = <SPI as spi::Write<u8>>::Error>,
+        F: FnMut(usize, &mut [u16]) -> usize,
+    {
+        if self.format != PixelFormat::Rgb565 || !strips.hold_a_pixel() {
+            return Err(Error::InvalidArgument);
+        }
+        if sx > ex || sy > ey {
+            return Ok(());
+        }
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.write_command(Instruction::RAMWR, None)?;
+        self.dc.set_high().map_err(Error::Dc)?;
+
+        let total = (ex as usize - sx as usize + 1) * (ey as usize - sy as usize + 1);
+        if self.send_strips(total, strips, sink, fill)? {
+            sink.wait().map_err(Error::Spi)?;
+        }
+        Ok(())
+    }
+
+    /// Renders up to `total` pixels with `fill` and sends them through
+    /// `sink`, encoding each strip while the previous one is in flight.
+    ///
+    /// `fill` renders a chunk at a time into a buffer on the stack, which
+    /// is converted and encoded into the strip. Returns whether the last
+    /// strip may still be in flight, in which case its buffer must be left
+    /// alone until `sink.wait()` has returned.
+    pub(crate) fn send_strips<S, F>(
+        &mut self,
+        total: usize,
+        strips: &mut StripBuffers,
+        sink: &mut S,
+        mut fill: F,
+    ) -> Result<bool, DisplayError<SPI, DC>>
+    where
+        S: StripSink<Error = <SPI as spi::Write<u8>>::Error>,
+        F: FnMut(usize, &mut [u16]) -> usize,
+    {
+        let mut chunk = [0u16; CHUNK_PIXELS];
+        let mut sent = 0;
+        let mut active = 0;
+        let mut in_flight = false;
+        let mut ended = false;
+        while sent < total && !ended {
+            let strip = &mut strips.buffers[active];
+            let mut count = 0;
+            while sent + count < total && 2 * (count + 1) <= strip.len() {
+                let max = chunk.len().min(total - sent - count).min(strip.len() / 2 - count);
+                let produced = fill(sent + count, &mut chunk[..max]).min(max);
+                if produced == 0 {
+                    ended = true;
+                    break;
+                }
+                for pixel in &mut chunk[..produced] {
+                    *pixel = self.convert_color(*pixel);
+                }
+                count += encode::to_be_bytes(&chunk[..produced], &mut strip[2 * count..]);
+            }
+            if count == 0 {
+                break;
+            }
+            if in_flight {
+                sink.wait().map_err(Error::Spi)?;
+            }
+            sink.start(&strip[..2 * count]).map_err(Error::Spi)?;
+            in_flight = true;
+            sent += count;
+            active ^= 1;
+        }
+        Ok(in_flight)
+    }
+}
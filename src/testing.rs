@@ -0,0 +1,278 @@
+//! A simulated panel for testing rendering code on the host.
+//!
+//! A driver built on [`MockSpi`], [`MockDc`] and [`MockPin`] sends its
+//! traffic into a [`MockPanel`], which interprets the commands the way
+//! the controller does and keeps a copy of frame memory:
+//!
+//! ```
+//! use core::cell::RefCell;
+//! use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+//! use st7735_lcd::ST7735;
+//!
+//! let mut gram = [0u16; GRAM_PIXELS];
+//! let mut log = [0u8; 256];
+//! let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+//! let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+//! display.init(&mut MockDelay).unwrap();
+//! display.set_pixel(10, 20, 0xF800).unwrap();
+//! assert_eq!(panel.borrow().pixel(10, 20), 0xF800);
+//! ```
+
+use crate::instruction::Instruction;
+use crate::scroll::{FRAME_COLUMNS, FRAME_LINES};
+use crate::ST7735;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Number of pixels in the frame memory kept by a [`MockPanel`].
+pub const GRAM_PIXELS: usize = FRAME_COLUMNS as usize * FRAME_LINES as usize;
+
+/// The state of a simulated controller.
+pub struct MockPanel<'a> {
+    gram: &'a mut [u16],
+    log: &'a mut [u8],
+    log_len: usize,
+    dc: bool,
+    command: u8,
+    /// Parameters received for the current command, up to the four used
+    params: [u8; 4],
+    param_count: usize,
+    columns: (u16, u16),
+    rows: (u16, u16),
+    madctl: u8,
+    colmod: u8,
+    /// Next pixel to write, relative to the window
+    cursor: (u16, u16),
+    /// Bytes of a pixel (or RGB444 pixel pair) received so far
+    pending: [u8; 3],
+    pending_len: usize,
+}
+
+impl<'a> MockPanel<'a> {
+    /// Creates a panel keeping frame memory in `gram`, which must hold
+    /// [`GRAM_PIXELS`] pixels, and recording commands into `log`.
+    ///
+    /// Commands that do not fit into `log` are still interpreted, just not
+    /// recorded.
+    pub fn new(gram: &'a mut [u16], log: &'a mut [u8]) -> Self {
+        assert!(gram.len() >= GRAM_PIXELS);
+        MockPanel {
+            gram,
+            log,
+            log_len: 0,
+            dc: false,
+            command: 0,
+            params: [0; 4],
+            param_count: 0,
+            columns: (0, FRAME_COLUMNS - 1),
+            rows: (0, FRAME_LINES - 1),
+            madctl: 0,
+            colmod: 0x06,
+            cursor: (0, 0),
+            pending: [0; 3],
+            pending_len: 0,
+        }
+    }
+
+    /// Returns the RGB565 color of a pixel of frame memory.
+    ///
+    /// Coordinates are those of frame memory, i.e. of the default portrait
+    /// orientation including any panel offset.
+    pub fn pixel(&self, x: u16, y: u16) -> u16 {
+        self.gram[y as usize * FRAME_COLUMNS as usize + x as usize]
+    }
+
+    /// Returns frame memory, row-major.
+    pub fn gram(&self) -> &[u16] {
+        &self.gram[..GRAM_PIXELS]
+    }
+
+    /// Returns the commands received so far, one byte each.
+    pub fn commands(&self) -> &[u8] {
+        &self.log[..self.log_len]
+    }
+
+    /// Forgets the recorded commands.
+    pub fn clear_commands(&mut self) {
+        self.log_len = 0;
+    }
+
+    /// Returns the last MADCTL value received.
+    pub fn madctl(&self) -> u8 {
+        self.madctl
+    }
+
+    /// Returns the last COLMOD value received.
+    pub fn colmod(&self) -> u8 {
+        self.colmod
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.dc {
+                self.data(byte);
+            } else {
+                self.start_command(byte);
+            }
+        }
+    }
+
+    fn start_command(&mut self, command: u8) {
+        self.finish_pixels();
+        if let Some(entry) = self.log.get_mut(self.log_len) {
+            *entry = command;
+            self.log_len += 1;
+        }
+        self.command = command;
+        self.param_count = 0;
+        if self.is(Instruction::RAMWR) {
+            self.cursor = (0, 0);
+        }
+    }
+
+    fn data(&mut self, byte: u8) {
+        if self.is(Instruction::RAMWR) {
+            self.pixel_byte(byte);
+            return;
+        }
+        if let Some(param) = self.params.get_mut(self.param_count) {
+            *param = byte;
+            self.param_count += 1;
+        }
+        let p = self.params;
+        let word = |i: usize| u16::from_be_bytes([p[i], p[i + 1]]);
+        match self.param_count {
+            4 if self.is(Instruction::CASET) => self.columns = (word(0), word(2)),
+            4 if self.is(Instruction::RASET) => self.rows = (word(0), word(2)),
+            1 if self.is(Instruction::MADCTL) => self.madctl = byte,
+            1 if self.is(Instruction::COLMOD) => self.colmod = byte & 0x07,
+            _ => {}
+        }
+    }
+
+    /// Returns whether the current command is `instr`.
+    fn is(&self, instr: Instruction) -> bool {
+        instr.to_u8() == Some(self.command)
+    }
+
+    fn pixel_byte(&mut self, byte: u8) {
+        self.pending[self.pending_len] = byte;
+        self.pending_len += 1;
+        let p = self.pending;
+        match (self.colmod, self.pending_len) {
+            // Like the controller, store each pixel once its 12 bits are in.
+            (0x03, 2) => {
+                self.put_pixel(rgb444_to_rgb565(p[0] >> 4, p[0] & 0x0F, p[1] >> 4));
+                return;
+            }
+            (0x03, 3) => self.put_pixel(rgb444_to_rgb565(p[1] & 0x0F, p[2] >> 4, p[2] & 0x0F)),
+            (0x05, 2) => self.put_pixel(u16::from_be_bytes([p[0], p[1]])),
+            (0x03, _) | (0x05, _) => return,
+            (_, 3) => self.put_pixel(((p[0] as u16 >> 3) << 11) | ((p[1] as u16 >> 2) << 5) | (p[2] as u16 >> 3)),
+            _ => return,
+        }
+        self.pending_len = 0;
+    }
+
+    /// Drops the bytes of an incomplete pixel, such as the padding an
+    /// odd-length RGB444 write ends with.
+    fn finish_pixels(&mut self) {
+        self.pending_len = 0;
+    }
+
+    /// Stores a pixel at the cursor and advances it through the window.
+    fn put_pixel(&mut self, color: u16) {
+        let (x, y) = (self.columns.0 + self.cursor.0, self.rows.0 + self.cursor.1);
+        let (mut col, mut row) = if self.madctl & 0x20 != 0 { (y, x) } else { (x, y) };
+        if self.madctl & 0x40 != 0 {
+            col = (FRAME_COLUMNS - 1).wrapping_sub(col);
+        }
+        if self.madctl & 0x80 != 0 {
+            row = (FRAME_LINES - 1).wrapping_sub(row);
+        }
+        if col < FRAME_COLUMNS && row < FRAME_LINES {
+            self.gram[row as usize * FRAME_COLUMNS as usize + col as usize] = color;
+        }
+        self.cursor.0 += 1;
+        if self.columns.0 + self.cursor.0 > self.columns.1 {
+            self.cursor.0 = 0;
+            self.cursor.1 += 1;
+            if self.rows.0 + self.cursor.1 > self.rows.1 {
+                self.cursor.1 = 0;
+            }
+        }
+    }
+}
+
+/// Expands 4-bit channels to an RGB565 pixel.
+fn rgb444_to_rgb565(r: u8, g: u8, b: u8) -> u16 {
+    let (r, g, b) = (r as u16, g as u16, b as u16);
+    (((r << 1) | (r >> 3)) << 11) | (((g << 2) | (g >> 2)) << 5) | ((b << 1) | (b >> 3))
+}
+
+/// SPI bus feeding a [`MockPanel`].
+pub struct MockSpi<'a, 'b>(pub &'a RefCell<MockPanel<'b>>);
+
+impl<'a, 'b> spi::Write<u8> for MockSpi<'a, 'b> {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.0.borrow_mut().write(words);
+        Ok(())
+    }
+}
+
+/// Data/command pin of a [`MockPanel`].
+pub struct MockDc<'a, 'b>(pub &'a RefCell<MockPanel<'b>>);
+
+impl<'a, 'b> OutputPin for MockDc<'a, 'b> {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().dc = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().dc = true;
+        Ok(())
+    }
+}
+
+/// A pin that does nothing, for the reset line.
+pub struct MockPin;
+
+impl OutputPin for MockPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// A delay that returns at once.
+pub struct MockDelay;
+
+impl DelayMs<u8> for MockDelay {
+    fn delay_ms(&mut self, _ms: u8) {}
+}
+
+impl<'a, 'b> ST7735<MockSpi<'a, 'b>, MockDc<'a, 'b>, MockPin> {
+    /// Creates a driver drawing into `panel`.
+    ///
+    /// The arguments are those of [`ST7735::new`].
+    pub fn new_mock(panel: &'a RefCell<MockPanel<'b>>, rgb: bool, inverted: bool, width: u16, height: u16) -> Self {
+        ST7735::new(MockSpi(panel), MockDc(panel), MockPin, rgb, inverted, width, height)
+    }
+}
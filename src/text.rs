@@ -0,0 +1,253 @@
+//! Word-wrapped text layout inside a rectangle.
+
+use crate::font::TextStyle;
+use crate::region::Region;
+use crate::{DisplayError, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Horizontal placement of each line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+/// How wrapped text is drawn and placed in its rectangle.
+#[derive(Clone, Copy, Debug)]
+pub struct WrapStyle {
+    /// Font and colors of the text.
+    pub text: TextStyle,
+
+    /// Placement of each line.
+    pub align: Alignment,
+
+    /// Whether text that does not fit ends in `...` on the last line.
+    pub ellipsis: bool,
+}
+
+impl WrapStyle {
+    /// Creates a left-aligned style without ellipsis.
+    pub const fn new(text: TextStyle) -> Self {
+        WrapStyle {
+            text,
+            align: Alignment::Left,
+            ellipsis: false,
+        }
+    }
+
+    /// Sets the placement of each line.
+    pub const fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets whether overflowing text is ellipsized.
+    pub const fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+}
+
+/// Where the next character of wrapped text goes, relative to the rectangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cursor {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Splits off the next line of at most `cols` characters.
+///
+/// Breaks at newlines, then at the last space that fits, and only cuts
+/// words longer than a line. Returns `None` as the rest once the text is
+/// used up.
+fn split_line(text: &str, cols: usize) -> (&str, Option<&str>) {
+    let mut last_space = None;
+    for (count, (i, c)) in text.char_indices().enumerate() {
+        if c == '\n' {
+            return (&text[..i], Some(&text[i + 1..]));
+        }
+        if count == cols {
+            if c == ' ' {
+                return (&text[..i], Some(&text[i + 1..]));
+            }
+            if let Some(space) = last_space {
+                return (&text[..space], Some(&text[space + 1..]));
+            }
+            return (&text[..i], Some(&text[i..]));
+        }
+        if c == ' ' {
+            last_space = Some(i);
+        }
+    }
+    (text, None)
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Draws `text` word-wrapped inside `rect`.
+    ///
+    /// Lines that do not fit are dropped, or with `style.ellipsis` the
+    /// last visible line ends in `...`. Returns the cursor after the last
+    /// character, or below the rectangle if the text did not fit, for
+    /// [`ST7735::append_text_wrapped`].
+    pub fn draw_text_wrapped(&mut self, rect: Region, text: &str, style: &WrapStyle) -> Result<Cursor, DisplayError<SPI, DC>> {
+        self.layout_text(rect, Cursor::default(), text, style)
+    }
+
+    /// Continues wrapped text at a cursor returned by an earlier call.
+    ///
+    /// Left-aligned text continues on the same line. Other alignments
+    /// cannot reflow a line that has already been drawn, so they start on
+    /// the next one.
+    pub fn append_text_wrapped(
+        &mut self,
+        rect: Region,
+        cursor: Cursor,
+        text: &str,
+        style: &WrapStyle,
+    ) -> Result<Cursor, DisplayError<SPI, DC>> {
+        let mut cursor = cursor;
+        if style.align != Alignment::Left && cursor.x != 0 {
+            cursor = Cursor {
+                x: 0,
+                y: cursor.y + style.text.font.height as u16,
+            };
+        }
+        self.layout_text(rect, cursor, text, style)
+    }
+
+    fn layout_text(&mut self, rect: Region, start: Cursor, text: &str, style: &WrapStyle) -> Result<Cursor, DisplayError<SPI, DC>> {
+        let font = style.text.font;
+        let (cw, ch) = (font.width as u16, font.height as u16);
+        if cw == 0 || ch == 0 || rect.width < cw {
+            return Ok(start);
+        }
+        let cols = rect.width / cw;
+        let rows = rect.height / ch;
+        let mut col = (start.x / cw).min(cols);
+        let mut row = start.y / ch;
+        let mut cursor = start;
+        let mut rest = Some(text);
+
+        while let Some(remaining) = rest {
+            if row >= rows {
+                break;
+            }
+            let capacity = cols - col;
+            let (line, next) = split_line(remaining, capacity as usize);
+            let overflow = row + 1 == rows && next.is_some_and(|n| !n.is_empty());
+
+            let chars = line.chars().count() as u16;
+            let (shown, dots) = if overflow && style.ellipsis {
+                let dots = capacity.min(3);
+                (chars.min(capacity - dots), dots)
+            } else {
+                (chars, 0)
+            };
+            let free = capacity - shown - dots;
+            let offset = match style.align {
+                Alignment::Left => 0,
+                Alignment::Center => free / 2,
+                Alignment::Right => free,
+            };
+            let x = (col + offset) * cw;
+            let y = row * ch;
+            let end = line.char_indices().nth(shown as usize).map_or(line.len(), |(i, _)| i);
+            let after = self.draw_text(rect.x + x, rect.y + y, &line[..end], &style.text)?;
+            self.draw_text(after, rect.y + y, &"..."[..dots as usize], &style.text)?;
+            cursor = Cursor {
+                x: x + (shown + dots) * cw,
+                y,
+            };
+
+            if overflow {
+                cursor = Cursor { x: 0, y: rows * ch };
+                break;
+            }
+            rest = next;
+            if rest.is_some() {
+                row += 1;
+                col = 0;
+                cursor = Cursor { x: 0, y: row * ch };
+            }
+        }
+        Ok(cursor)
+    }
+}
+
+/// Draws formatted text word-wrapped into a rectangle, for use with
+/// `write!`.
+///
+/// Each piece of text continues at the cursor left by the previous one.
+/// The first drawing error makes every later write fail, and can be
+/// taken with [`TextWriter::take_error`].
+pub struct TextWriter<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7735<SPI, DC, RST>,
+    rect: Region,
+    style: WrapStyle,
+    cursor: Cursor,
+    error: Option<DisplayError<SPI, DC>>,
+}
+
+impl<'a, SPI, DC, RST> TextWriter<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a writer starting at the top left of `rect`.
+    pub fn new(display: &'a mut ST7735<SPI, DC, RST>, rect: Region, style: WrapStyle) -> Self {
+        TextWriter {
+            display,
+            rect,
+            style,
+            cursor: Cursor::default(),
+            error: None,
+        }
+    }
+
+    /// Returns where the next character goes.
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    /// Returns the drawing error that stopped the writer, if any.
+    pub fn take_error(&mut self) -> Option<DisplayError<SPI, DC>> {
+        self.error.take()
+    }
+}
+
+impl<'a, SPI, DC, RST> core::fmt::Write for TextWriter<'a, SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.error.is_some() {
+            return Err(core::fmt::Error);
+        }
+        match self.display.append_text_wrapped(self.rect, self.cursor, s, &self.style) {
+            Ok(cursor) => {
+                self.cursor = cursor;
+                Ok(())
+            }
+            Err(e) => {
+                self.error = Some(e);
+                Err(core::fmt::Error)
+            }
+        }
+    }
+}
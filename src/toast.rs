@@ -0,0 +1,139 @@
+//! Transient notification banners over a buffered screen.
+
+use crate::font::TextStyle;
+use crate::hybrid::Hybrid;
+use crate::region::Region;
+use crate::DisplayError;
+
+use core::iter::repeat_n;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Space between the banner edge and its text, in pixels.
+const PADDING: u16 = 4;
+
+/// Corner radius of the banner, in pixels.
+const RADIUS: u16 = 4;
+
+/// Gap between the banner and the bottom of the panel, in pixels.
+const MARGIN: u16 = 4;
+
+/// A message banner shown near the bottom of the panel for a while.
+///
+/// The banner is drawn straight to the panel, past the [`Hybrid`] buffer,
+/// so the buffer still holds what is underneath and dismissing the toast
+/// only resends the covered area. Buffered drawing that is flushed while
+/// the toast is visible may overwrite it.
+#[derive(Clone, Copy, Debug)]
+pub struct Toast {
+    style: TextStyle,
+    shown: Option<(Region, u32)>,
+}
+
+impl Toast {
+    /// Creates a hidden toast drawing text in `style`.
+    ///
+    /// The style's background, or black if it has none, fills the banner.
+    pub const fn new(style: TextStyle) -> Self {
+        Toast { style, shown: None }
+    }
+
+    /// Returns whether the toast is on screen.
+    pub fn is_visible(&self) -> bool {
+        self.shown.is_some()
+    }
+
+    /// Shows `text` for `duration_ms`, replacing any toast on screen.
+    pub fn show_toast<SPI, DC, RST>(
+        &mut self,
+        target: &mut Hybrid<SPI, DC, RST>,
+        text: &str,
+        duration_ms: u32,
+    ) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        self.dismiss(target)?;
+
+        let font = self.style.font;
+        let (cw, ch) = (font.width as u16, font.height as u16);
+        let (width, height) = target.size();
+        let cols = width.saturating_sub(2 * PADDING).checked_div(cw).unwrap_or(0) as usize;
+        let end = text.char_indices().nth(cols).map_or(text.len(), |(i, _)| i);
+        let text = &text[..end];
+        let banner_width = text.chars().count() as u16 * cw + 2 * PADDING;
+        let banner_height = ch + 2 * PADDING;
+        if banner_width > width || banner_height + MARGIN > height {
+            return Ok(());
+        }
+        let banner = Region::new(
+            (width - banner_width) / 2,
+            height - MARGIN - banner_height,
+            banner_width,
+            banner_height,
+        );
+
+        let mut style = self.style;
+        let background = style.background.unwrap_or(0);
+        style.background = Some(background);
+        self.shown = Some((banner, duration_ms));
+
+        let display = target.display();
+        for row in 0..banner.height {
+            let inset = corner_inset(row, banner.height);
+            let count = (banner.width - 2 * inset) as usize;
+            let y = banner.y + row;
+            display.set_pixels(banner.x + inset, y, banner.x + banner.width - inset - 1, y, repeat_n(background, count))?;
+        }
+        display.draw_text(banner.x + PADDING, banner.y + PADDING, text, &style)?;
+        Ok(())
+    }
+
+    /// Advances time by `dt_ms`, dismissing the toast once it has expired.
+    ///
+    /// Returns whether the toast is still visible.
+    pub fn tick<SPI, DC, RST>(&mut self, target: &mut Hybrid<SPI, DC, RST>, dt_ms: u32) -> Result<bool, DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if let Some((banner, remaining)) = self.shown {
+            if remaining > dt_ms {
+                self.shown = Some((banner, remaining - dt_ms));
+                return Ok(true);
+            }
+            self.dismiss(target)?;
+        }
+        Ok(false)
+    }
+
+    /// Hides the toast, restoring the pixels underneath from the buffer.
+    pub fn dismiss<SPI, DC, RST>(&mut self, target: &mut Hybrid<SPI, DC, RST>) -> Result<(), DisplayError<SPI, DC>>
+    where
+        SPI: spi::Write<u8>,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if let Some((banner, _)) = self.shown.take() {
+            target.refresh(banner)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns how far row `row` of a rounded banner starts from its edges.
+fn corner_inset(row: u16, height: u16) -> u16 {
+    let radius = RADIUS.min(height / 2) as u32;
+    let row = row.min(height - 1 - row) as u32;
+    if row >= radius {
+        return 0;
+    }
+    // Distance of the row's center from the corner circle's center, doubled
+    let d = 2 * (radius - row) - 1;
+    let r = 2 * radius;
+    ((r - (r * r - d * d).isqrt()) / 2) as u16
+}
@@ -0,0 +1,167 @@
+//! Tuning of the controller's frame rate and power supply.
+//!
+//! `init` sends the settings of the [`Model`](crate::model::Model); the
+//! methods here change them afterwards, and have to be called again after
+//! every `init`.
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, ST7735};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Line period and porches of one display mode, as set by FRMCTR1-3.
+///
+/// On the ST7735S the frame rate is
+/// `850 kHz / ((rtna * 2 + 40) * (lines + fpa + bpa + 2))`; longer lines
+/// and porches lower the rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameTiming {
+    /// Line period, 0 to 15.
+    pub rtna: u8,
+
+    /// Front porch in lines, 1 to 63.
+    pub fpa: u8,
+
+    /// Back porch in lines, 1 to 63.
+    pub bpa: u8,
+}
+
+impl FrameTiming {
+    /// Creates a timing from its register values.
+    pub const fn new(rtna: u8, fpa: u8, bpa: u8) -> Self {
+        FrameTiming { rtna, fpa, bpa }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.rtna <= 0x0F && (1..=0x3F).contains(&self.fpa) && (1..=0x3F).contains(&self.bpa)
+    }
+
+    fn params(&self) -> [u8; 3] {
+        [self.rtna, self.fpa, self.bpa]
+    }
+}
+
+/// Frame timings of the normal, idle and partial display modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameRateConfig {
+    /// Timing in normal mode (FRMCTR1).
+    pub normal: FrameTiming,
+
+    /// Timing in idle mode (FRMCTR2).
+    pub idle: FrameTiming,
+
+    /// Timing in partial mode (FRMCTR3).
+    pub partial: FrameTiming,
+}
+
+impl FrameRateConfig {
+    /// Uses `timing` in every display mode.
+    pub const fn uniform(timing: FrameTiming) -> Self {
+        FrameRateConfig {
+            normal: timing,
+            idle: timing,
+            partial: timing,
+        }
+    }
+}
+
+/// Common frame rates of a 160 line panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameRate {
+    /// About 60 Hz, the ST7735S reset default.
+    Hz60,
+
+    /// About 80 Hz, with the values [`ST7735::init`] sends.
+    Hz80,
+}
+
+impl From<FrameRate> for FrameRateConfig {
+    fn from(rate: FrameRate) -> Self {
+        let timing = match rate {
+            FrameRate::Hz60 => FrameTiming::new(0x05, 0x3C, 0x3C),
+            FrameRate::Hz80 => FrameTiming::new(0x01, 0x2C, 0x2D),
+        };
+        FrameRateConfig::uniform(timing)
+    }
+}
+
+/// Power control settings, sent as PWCTR1-5 and VMCTR1.
+///
+/// The fields hold the raw register parameters; see the controller's
+/// datasheet for their meaning. Clone panels sometimes need different
+/// AVDD, GVDD or VCOM levels than the defaults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerConfig {
+    /// AVDD, GVDD and the mode of the last of them (PWCTR1).
+    pub pwctr1: [u8; 3],
+
+    /// VGH and VGL supply levels (PWCTR2).
+    pub pwctr2: u8,
+
+    /// Op amp current and booster frequency in normal mode (PWCTR3).
+    pub pwctr3: [u8; 2],
+
+    /// Op amp current and booster frequency in idle mode (PWCTR4).
+    pub pwctr4: [u8; 2],
+
+    /// Op amp current and booster frequency in partial mode (PWCTR5).
+    pub pwctr5: [u8; 2],
+
+    /// VCOM voltage (VMCTR1).
+    pub vmctr1: u8,
+}
+
+impl PowerConfig {
+    /// The values [`ST7735::init`] sends.
+    pub const ST7735R: PowerConfig = PowerConfig {
+        pwctr1: [0xA2, 0x02, 0x84],
+        pwctr2: 0xC5,
+        pwctr3: [0x0A, 0x00],
+        pwctr4: [0x8A, 0x2A],
+        pwctr5: [0x8A, 0xEE],
+        vmctr1: 0x0E,
+    };
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        PowerConfig::ST7735R
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sets the frame timings, e.g. `set_frame_rate(FrameRate::Hz60.into())`.
+    ///
+    /// A lower rate saves power but may flicker. Fails with
+    /// [`Error::InvalidArgument`] if a value is out of range.
+    pub fn set_frame_rate(&mut self, config: FrameRateConfig) -> Result<(), DisplayError<SPI, DC>> {
+        let FrameRateConfig { normal, idle, partial } = config;
+        if !normal.is_valid() || !idle.is_valid() || !partial.is_valid() {
+            return Err(Error::InvalidArgument);
+        }
+        let [rtna, fpa, bpa] = partial.params();
+        self.write_command(Instruction::FRMCTR1, Some(&normal.params()))?;
+        self.write_command(Instruction::FRMCTR2, Some(&idle.params()))?;
+        self.write_command(Instruction::FRMCTR3, Some(&[rtna, fpa, bpa, rtna, fpa, bpa]))
+    }
+
+    /// Sets the power control registers.
+    pub fn set_power_config(&mut self, config: &PowerConfig) -> Result<(), DisplayError<SPI, DC>> {
+        self.write_command(Instruction::PWCTR1, Some(&config.pwctr1))?;
+        self.write_command(Instruction::PWCTR2, Some(&[config.pwctr2]))?;
+        self.write_command(Instruction::PWCTR3, Some(&config.pwctr3))?;
+        self.write_command(Instruction::PWCTR4, Some(&config.pwctr4))?;
+        self.write_command(Instruction::PWCTR5, Some(&config.pwctr5))?;
+        self.write_command(Instruction::VMCTR1, Some(&[config.vmctr1]))
+    }
+}
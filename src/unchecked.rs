@@ -0,0 +1,45 @@
+//! Sending raw commands the driver does not know about.
+
+use crate::instruction::Instruction;
+use crate::{DisplayError, Error, ST7735};
+
+use num_traits::ToPrimitive;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Sends `command` followed by `params`, e.g. for vendor-specific or
+    /// undocumented commands of a particular panel.
+    ///
+    /// The driver does not follow what the command changes: sending
+    /// MADCTL or COLMOD this way leaves the size, offsets and pixel format
+    /// it works with as they were, and drawing may come out wrong.
+    pub fn send_command(&mut self, command: u8, params: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        self.finish_pixels()?;
+        self.command_seq = self.command_seq.wrapping_add(1);
+        if let (Some(madctl), Some(&value)) = (Instruction::MADCTL.to_u8(), params.first()) {
+            if command == madctl {
+                self.madctl = value;
+            }
+        }
+        self.dc.set_low().map_err(Error::Dc)?;
+        self.bus_write(&[command], false)?;
+        if !params.is_empty() {
+            self.write_data(params)?;
+        }
+        Ok(())
+    }
+
+    /// Sends `data` as further parameters or pixel data of the last
+    /// command.
+    pub fn send_data(&mut self, data: &[u8]) -> Result<(), DisplayError<SPI, DC>> {
+        self.finish_pixels()?;
+        self.write_data(data)
+    }
+}
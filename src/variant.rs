@@ -0,0 +1,116 @@
+//! Presets for the common panel variants.
+
+use crate::ST7735;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// Positive gamma correction sent by `init` for a [`Variant`].
+pub(crate) const GAMMA_POSITIVE: [u8; 16] = [
+    0x02, 0x1C, 0x07, 0x12, 0x37, 0x32, 0x29, 0x2D, 0x29, 0x25, 0x2B, 0x39, 0x00, 0x01, 0x03, 0x10,
+];
+
+/// Negative gamma correction sent by `init` for a [`Variant`].
+pub(crate) const GAMMA_NEGATIVE: [u8; 16] = [
+    0x03, 0x1D, 0x07, 0x06, 0x2E, 0x2C, 0x29, 0x2D, 0x2E, 0x2E, 0x37, 0x3F, 0x00, 0x00, 0x02, 0x10,
+];
+
+/// A panel variant, named after the colour of the tab on the screen
+/// protector of the 1.8" modules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Variant {
+    /// ST7735R, 128x160 with a red tab.
+    RedTab,
+
+    /// ST7735R, 128x160 with a green tab, shifted in frame memory.
+    GreenTab,
+
+    /// ST7735S, 128x160 with a black tab and RGB order.
+    BlackTab,
+
+    /// ST7735S, 0.96" 80x160, inverted and shifted in frame memory.
+    Mini160x80,
+
+    /// ST7735R, 1.44" 128x128 with a green tab, shifted in frame memory.
+    GreenTab128x128,
+}
+
+impl Variant {
+    /// Returns the `(width, height)` of the panel in portrait.
+    pub fn size(&self) -> (u16, u16) {
+        match self {
+            Variant::RedTab | Variant::GreenTab | Variant::BlackTab => (128, 160),
+            Variant::Mini160x80 => (80, 160),
+            Variant::GreenTab128x128 => (128, 128),
+        }
+    }
+
+    /// Returns where the panel sits in frame memory in portrait.
+    pub fn offset(&self) -> (u16, u16) {
+        match self {
+            Variant::RedTab | Variant::BlackTab => (0, 0),
+            Variant::GreenTab => (2, 1),
+            Variant::Mini160x80 => (26, 1),
+            Variant::GreenTab128x128 => (2, 3),
+        }
+    }
+
+    /// Returns the `(width, height)` of the frame memory the panel is
+    /// mapped into, which mirrored orientations count offsets from.
+    pub fn gram_size(&self) -> (u16, u16) {
+        match self {
+            Variant::RedTab | Variant::BlackTab => (128, 160),
+            Variant::GreenTab | Variant::Mini160x80 | Variant::GreenTab128x128 => (132, 162),
+        }
+    }
+
+    /// Returns the variant of a panel that is `width` by `height` in
+    /// portrait, if there is one.
+    ///
+    /// 128x160 panels are taken to be green tab ones, as red and black tab
+    /// panels fill their frame memory and need no offset either way.
+    pub(crate) fn of_size(width: u16, height: u16) -> Option<Variant> {
+        [Variant::GreenTab, Variant::Mini160x80, Variant::GreenTab128x128]
+            .iter()
+            .copied()
+            .find(|variant| variant.size() == (width, height))
+    }
+
+    /// Returns whether the panel is RGB (true) or BGR (false).
+    pub fn rgb(&self) -> bool {
+        matches!(self, Variant::BlackTab)
+    }
+
+    /// Returns whether the panel needs its colours inverted.
+    pub fn inverted(&self) -> bool {
+        matches!(self, Variant::Mini160x80)
+    }
+}
+
+impl<SPI, DC, RST> ST7735<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Creates a driver set up for `variant`.
+    ///
+    /// Size, colour order, inversion and the offsets of every orientation
+    /// are taken from the variant, and `init` also sends its gamma
+    /// correction.
+    pub fn with_variant(spi: SPI, dc: DC, rst: RST, variant: Variant) -> Self {
+        let (width, height) = variant.size();
+        let mut display = ST7735::new(spi, dc, rst, variant.rgb(), variant.inverted(), width, height);
+        let (dx, dy) = variant.offset();
+        let (gram_width, gram_height) = variant.gram_size();
+        display.set_gram_offset(dx, dy, gram_width, gram_height);
+        display.variant = Some(variant);
+        display
+    }
+
+    /// Returns the variant the driver was created for, if any.
+    pub fn variant(&self) -> Option<Variant> {
+        self.variant
+    }
+}
@@ -0,0 +1,160 @@
+//! Checks that dropping async drawing futures mid-transfer leaves the next
+//! call a fresh window.
+//!
+//! Run on the host with
+//! `cargo test --tests --features async --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "async")]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use std::rc::Rc;
+
+use embedded_hal_1::digital::{ErrorType as PinErrorType, OutputPin};
+use embedded_hal_async::spi::{ErrorType, Operation, SpiDevice};
+
+use st7735_lcd::asynch::ST7735;
+use st7735_lcd::region::Region;
+
+/// A bus write, tagged with whether DC was high (data) or low (command).
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Write {
+    Command(Vec<u8>),
+    Data(Vec<u8>),
+}
+
+#[derive(Default)]
+struct Bus {
+    dc: bool,
+    writes: Vec<Write>,
+    /// Number of further writes that complete before the bus stalls
+    stall_after: Option<usize>,
+}
+
+impl Bus {
+    /// Lets writes complete again and forgets those recorded.
+    fn resume(&mut self) {
+        self.stall_after = None;
+        self.writes.clear();
+    }
+}
+
+struct MockSpi(Rc<RefCell<Bus>>);
+
+impl ErrorType for MockSpi {
+    type Error = Infallible;
+}
+
+impl SpiDevice for MockSpi {
+    async fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+        let stalled = match &mut self.0.borrow_mut().stall_after {
+            Some(0) => true,
+            Some(left) => {
+                *left -= 1;
+                false
+            }
+            None => false,
+        };
+        if stalled {
+            core::future::pending::<()>().await;
+        }
+        let mut bus = self.0.borrow_mut();
+        for operation in operations.iter() {
+            if let Operation::Write(bytes) = operation {
+                let write = if bus.dc { Write::Data(bytes.to_vec()) } else { Write::Command(bytes.to_vec()) };
+                bus.writes.push(write);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct MockPin(Option<Rc<RefCell<Bus>>>);
+
+impl PinErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        if let Some(bus) = &self.0 {
+            bus.borrow_mut().dc = false;
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        if let Some(bus) = &self.0 {
+            bus.borrow_mut().dc = true;
+        }
+        Ok(())
+    }
+}
+
+/// Polls `future` once and returns whether it is still pending.
+fn poll_once<F: Future>(future: F) -> bool {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    future.as_mut().poll(&mut cx).is_pending()
+}
+
+/// Polls `future`, which must not stall, to completion.
+fn run<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    match future.as_mut().poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future stalled"),
+    }
+}
+
+fn display(bus: &Rc<RefCell<Bus>>) -> ST7735<MockSpi, MockPin, MockPin> {
+    ST7735::new(MockSpi(bus.clone()), MockPin(Some(bus.clone())), MockPin(None), true, false, 128, 160)
+}
+
+/// The writes of a single pixel at `(10, 20)`.
+fn fresh_pixel() -> Vec<Write> {
+    vec![
+        Write::Command(vec![0x2A]),
+        Write::Data(vec![0x00, 0x0A, 0x00, 0x0A]),
+        Write::Command(vec![0x2B]),
+        Write::Data(vec![0x00, 0x14, 0x00, 0x14]),
+        Write::Command(vec![0x2C]),
+        Write::Data(vec![0xAB, 0xCD]),
+    ]
+}
+
+#[test]
+fn dropped_set_pixels_leaves_the_next_call_a_fresh_window() {
+    let bus = Rc::new(RefCell::new(Bus::default()));
+    let mut display = display(&bus);
+
+    // The window is set up, then the bus stalls on the pixel data.
+    bus.borrow_mut().stall_after = Some(5);
+    assert!(poll_once(display.set_pixels(0, 0, 63, 1, core::iter::repeat_n(0x1234, 128))));
+    assert_eq!(bus.borrow().writes.len(), 5);
+    assert!(bus.borrow().dc);
+
+    bus.borrow_mut().resume();
+    run(display.set_pixels(10, 20, 10, 20, [0xABCD])).unwrap();
+    assert_eq!(bus.borrow().writes, fresh_pixel());
+}
+
+#[test]
+fn dropped_flush_region_leaves_the_next_call_a_fresh_window() {
+    let bus = Rc::new(RefCell::new(Bus::default()));
+    let mut display = display(&bus);
+    let pixels = [0x1234u16; 128];
+
+    // The bus stalls on RASET, with DC low.
+    bus.borrow_mut().stall_after = Some(2);
+    assert!(poll_once(display.flush_region(Region::new(0, 0, 64, 2), &pixels)));
+    assert!(!bus.borrow().dc);
+
+    bus.borrow_mut().resume();
+    run(display.flush_region(Region::new(10, 20, 1, 1), &[0xABCD])).unwrap();
+    assert_eq!(bus.borrow().writes, fresh_pixel());
+}
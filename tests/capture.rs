@@ -0,0 +1,36 @@
+//! Checks that replaying a capture leaves the driver matching the panel.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing,capture --target x86_64-unknown-linux-gnu`.
+
+#![cfg(all(feature = "testing", feature = "capture"))]
+
+use core::cell::RefCell;
+
+use st7735_lcd::capture::{Capture, CaptureDc, CaptureDelay, CaptureSpi};
+use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+use st7735_lcd::{NoResetPin, Orientation, PixelFormat, ST7735};
+
+#[test]
+fn replay_follows_madctl_and_colmod() {
+    let mut buf = vec![0u8; 1024];
+    let capture = RefCell::new(Capture::new(&mut buf));
+    let mut recorder = ST7735::new(CaptureSpi(&capture), CaptureDc(&capture), NoResetPin::new(), true, false, 128, 160);
+    recorder.init(&mut CaptureDelay(&capture)).unwrap();
+    recorder.set_orientation(&Orientation::Landscape).unwrap();
+    recorder.set_pixel_format(PixelFormat::Rgb444).unwrap();
+    let capture = capture.into_inner();
+
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.replay(capture.stream(), &mut MockDelay).unwrap();
+    assert_eq!(display.size(), (160, 128));
+    assert_eq!(display.orientation(), Orientation::Landscape);
+    assert_eq!(display.pixel_format(), PixelFormat::Rgb444);
+
+    // The last landscape column is frame memory row 159.
+    display.set_pixel(159, 0, 0xF800).unwrap();
+    assert_eq!(panel.borrow().pixel(131, 159), 0xF800);
+}
@@ -0,0 +1,34 @@
+//! Checks the pixel encoding helpers independently of the host's byte order.
+//!
+//! Run on the host with `cargo test --tests --target x86_64-unknown-linux-gnu`.
+
+use st7735_lcd::encode;
+
+#[test]
+fn to_be_bytes_puts_the_high_byte_first() {
+    let mut out = [0u8; 6];
+    assert_eq!(encode::to_be_bytes(&[0xF800, 0x07E0, 0x1234], &mut out), 3);
+    assert_eq!(out, [0xF8, 0x00, 0x07, 0xE0, 0x12, 0x34]);
+}
+
+#[test]
+fn to_be_bytes_converts_odd_lengths() {
+    let mut out = [0xAAu8; 8];
+    assert_eq!(encode::to_be_bytes(&[0x0102, 0x0304, 0x0506], &mut out), 3);
+    assert_eq!(out, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xAA, 0xAA]);
+}
+
+#[test]
+fn to_be_bytes_stops_at_a_short_buffer() {
+    let mut out = [0xAAu8; 5];
+    assert_eq!(encode::to_be_bytes(&[0x0102, 0x0304, 0x0506], &mut out), 2);
+    assert_eq!(out, [0x01, 0x02, 0x03, 0x04, 0xAA]);
+}
+
+#[test]
+fn to_be_bytes_handles_empty_input_and_output() {
+    let mut out = [0xAAu8; 1];
+    assert_eq!(encode::to_be_bytes(&[], &mut out), 0);
+    assert_eq!(encode::to_be_bytes(&[0x1234], &mut out), 0);
+    assert_eq!(out, [0xAA]);
+}
@@ -0,0 +1,117 @@
+//! Checks the `display-interface` adapter with a reset pin that can fail.
+//!
+//! Run on the host with
+//! `cargo test --tests --features interface --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "interface")]
+
+use core::cell::RefCell;
+use std::rc::Rc;
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::digital::v2::OutputPin;
+
+use st7735_lcd::interface::Interface;
+use st7735_lcd::{Error, ST7735};
+
+/// A bus write, tagged with whether it went out as commands or data.
+#[derive(Debug, PartialEq, Eq)]
+enum Write {
+    Command(Vec<u8>),
+    Data(Vec<u8>),
+}
+
+/// Writes seen by the transport, which fails them all when `fail` is set.
+#[derive(Default)]
+struct Log {
+    writes: Vec<Write>,
+    fail: bool,
+}
+
+struct Transport(Rc<RefCell<Log>>);
+
+impl Transport {
+    fn write(&mut self, write: Write) -> Result<(), DisplayError> {
+        let mut log = self.0.borrow_mut();
+        if log.fail {
+            return Err(DisplayError::BusWriteError);
+        }
+        log.writes.push(write);
+        Ok(())
+    }
+}
+
+fn bytes(format: DataFormat<'_>) -> Vec<u8> {
+    match format {
+        DataFormat::U8(bytes) => bytes.to_vec(),
+        _ => panic!("unexpected data format"),
+    }
+}
+
+impl WriteOnlyDataCommand for Transport {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.write(Write::Command(bytes(cmd)))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.write(Write::Data(bytes(buf)))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PinError;
+
+struct ResetPin;
+
+impl OutputPin for ResetPin {
+    type Error = PinError;
+
+    fn set_low(&mut self) -> Result<(), PinError> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), PinError> {
+        Ok(())
+    }
+}
+
+struct Delay;
+
+impl DelayMs<u8> for Delay {
+    fn delay_ms(&mut self, _ms: u8) {}
+}
+
+#[test]
+fn init_runs_with_a_fallible_reset_pin() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let interface = RefCell::new(Interface::new(Transport(log.clone())));
+    let mut display = ST7735::new_interface(&interface, ResetPin, true, false, 128, 160);
+    display.init(&mut Delay).unwrap();
+    log.borrow_mut().writes.clear();
+
+    display.set_pixel(10, 20, 0xABCD).unwrap();
+    assert_eq!(
+        log.borrow().writes,
+        [
+            Write::Command(vec![0x2A]),
+            Write::Data(vec![0x00, 0x0A]),
+            Write::Data(vec![0x00, 0x0A]),
+            Write::Command(vec![0x2B]),
+            Write::Data(vec![0x00, 0x14]),
+            Write::Data(vec![0x00, 0x14]),
+            Write::Command(vec![0x2C]),
+            Write::Data(vec![0xAB, 0xCD]),
+        ]
+    );
+}
+
+#[test]
+fn transport_errors_are_reported_as_bus_errors() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let interface = RefCell::new(Interface::new(Transport(log.clone())));
+    let mut display = ST7735::new_interface(&interface, ResetPin, true, false, 128, 160);
+    display.init(&mut Delay).unwrap();
+    log.borrow_mut().fail = true;
+    assert!(matches!(display.set_pixel(0, 0, 0), Err(Error::Spi(DisplayError::BusWriteError))));
+}
@@ -0,0 +1,85 @@
+//! Checks which rows the list widget redraws, on the simulated panel.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "testing")]
+
+use core::cell::RefCell;
+
+use st7735_lcd::font::{TextStyle, FONT_6X8};
+use st7735_lcd::list::{List, ListStyle};
+use st7735_lcd::region::Region;
+use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+use st7735_lcd::ST7735;
+
+const LABELS: [&str; 10] = [
+    "item0", "item1", "item2", "item3", "item4", "item5", "item6", "item7", "item8", "item9",
+];
+
+const STYLE: ListStyle = ListStyle {
+    normal: TextStyle::new(&FONT_6X8, 0xFFFF).background(0x0000),
+    selected: TextStyle::new(&FONT_6X8, 0x0000).background(0xFFFF),
+};
+
+const RAMWR: u8 = 0x2C;
+const VSCSAD: u8 = 0x37;
+
+fn count(commands: &[u8], command: u8) -> usize {
+    commands.iter().filter(|&&c| c == command).count()
+}
+
+#[test]
+fn single_step_scroll_draws_only_the_exposed_and_deselected_rows() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 4096];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    panel.borrow_mut().clear_commands();
+    let mut list = List::new(Region::new(0, 0, 128, 32), STYLE, LABELS.len(), |i| LABELS[i]);
+    assert_eq!(list.visible_rows(), 4);
+
+    list.draw(&mut display).unwrap();
+    let full = count(panel.borrow().commands(), RAMWR);
+    assert!(panel.borrow().commands().contains(&VSCSAD));
+
+    panel.borrow_mut().clear_commands();
+    list.select(&mut display, 3).unwrap();
+    let moved = count(panel.borrow().commands(), RAMWR);
+    assert_eq!(moved, full / 2);
+    assert!(!panel.borrow().commands().contains(&VSCSAD));
+
+    panel.borrow_mut().clear_commands();
+    list.select(&mut display, 4).unwrap();
+    assert_eq!(list.top(), 1);
+    assert_eq!(count(panel.borrow().commands(), VSCSAD), 1);
+    assert_eq!(count(panel.borrow().commands(), RAMWR), full / 2);
+
+    // The top slot scrolled to the bottom and now holds the selected item,
+    // the row above it was deselected and the others were left alone.
+    let panel = panel.borrow();
+    assert_eq!(panel.pixel(127, 0), 0xFFFF);
+    assert_eq!(panel.pixel(127, 8), 0x0000);
+    assert_eq!(panel.pixel(127, 24), 0x0000);
+}
+
+#[test]
+fn lists_narrower_than_the_panel_redraw_every_row() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 4096];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    panel.borrow_mut().clear_commands();
+    let mut list = List::new(Region::new(0, 0, 120, 32), STYLE, LABELS.len(), |i| LABELS[i]);
+
+    list.draw(&mut display).unwrap();
+    let full = count(panel.borrow().commands(), RAMWR);
+    assert!(!panel.borrow().commands().contains(&VSCSAD));
+
+    panel.borrow_mut().clear_commands();
+    list.scroll_to(&mut display, 1).unwrap();
+    assert_eq!(count(panel.borrow().commands(), RAMWR), full);
+    assert!(!panel.borrow().commands().contains(&VSCSAD));
+}
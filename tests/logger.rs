@@ -0,0 +1,99 @@
+//! Checks how the on-screen logger fills and scrolls its region, on the
+//! simulated panel.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing,log --target x86_64-unknown-linux-gnu`.
+
+#![cfg(all(feature = "testing", feature = "log"))]
+
+use core::cell::RefCell;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use log::{Level, LevelFilter, Log, Record};
+
+use st7735_lcd::logger::ScreenLogger;
+use st7735_lcd::region::Region;
+use st7735_lcd::testing::{MockDc, MockDelay, MockPanel, MockPin, MockSpi, GRAM_PIXELS};
+use st7735_lcd::ST7735;
+
+const VSCSAD: u8 = 0x37;
+
+/// Makes a mock half sendable, as `Log` requires. The tests stay on one
+/// thread, so the shared panel is never reached from two at once.
+struct Local<T>(T);
+
+unsafe impl<T> Send for Local<T> {}
+
+impl<'a, 'b> spi::Write<u8> for Local<MockSpi<'a, 'b>> {
+    type Error = core::convert::Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(words)
+    }
+}
+
+impl<'a, 'b> OutputPin for Local<MockDc<'a, 'b>> {
+    type Error = core::convert::Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high()
+    }
+}
+
+fn display<'a, 'b>(panel: &'a RefCell<MockPanel<'b>>) -> ST7735<Local<MockSpi<'a, 'b>>, Local<MockDc<'a, 'b>>, MockPin> {
+    let mut display = ST7735::new(Local(MockSpi(panel)), Local(MockDc(panel)), MockPin, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    panel.borrow_mut().clear_commands();
+    display
+}
+
+fn log(logger: &impl Log, level: Level, message: &str) {
+    logger.log(&Record::builder().level(level).args(format_args!("{}", message)).build());
+}
+
+#[test]
+fn full_width_logs_scroll_in_hardware() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log_bytes = vec![0u8; 4096];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log_bytes));
+    let display = display(&panel);
+    let logger = ScreenLogger::new(display, Region::new(0, 0, 128, 16), LevelFilter::Info);
+
+    log(&logger, Level::Error, "one");
+    log(&logger, Level::Info, "two");
+    let vscsad = |panel: &RefCell<MockPanel>| panel.borrow().commands().iter().filter(|&&c| c == VSCSAD).count();
+    // Setting up the scroll area resets the offset once.
+    assert_eq!(vscsad(&panel), 1);
+
+    log(&logger, Level::Info, "three");
+    assert_eq!(vscsad(&panel), 2);
+    // The third record replaced the first one, in the top line of memory
+    // which is now shown at the bottom; its level is drawn in white.
+    let panel = panel.borrow();
+    assert!((0..8).any(|y| (0..30).any(|x| panel.pixel(x, y) == 0xFFFF)));
+    assert!(!(0..8).any(|y| (0..30).any(|x| panel.pixel(x, y) == 0xF800)));
+}
+
+#[test]
+fn narrow_logs_wrap_to_the_top() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log_bytes = vec![0u8; 4096];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log_bytes));
+    let display = display(&panel);
+    let logger = ScreenLogger::new(display, Region::new(0, 0, 96, 16), LevelFilter::Info);
+
+    log(&logger, Level::Error, "one");
+    log(&logger, Level::Info, "two");
+    log(&logger, Level::Warn, "three");
+    let panel = panel.borrow();
+    assert!(!panel.commands().contains(&VSCSAD));
+    assert!((0..8).any(|y| (0..30).any(|x| panel.pixel(x, y) == 0xFFE0)));
+    // The line after the newest one is kept blank.
+    assert!((8..16).all(|y| (0..96).all(|x| panel.pixel(x, y) == 0x0000)));
+}
@@ -0,0 +1,132 @@
+//! Checks that drawing calls reaching the end of the `u16` coordinate
+//! space clip instead of overflowing.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "testing")]
+
+use core::cell::RefCell;
+use core::iter::repeat_n;
+
+use st7735_lcd::testing::{MockDc, MockDelay, MockPanel, MockPin, MockSpi, GRAM_PIXELS};
+use st7735_lcd::ST7735;
+
+/// Runs `draw` on an initialized simulated 128x160 panel and returns its
+/// frame memory.
+fn draw_on_panel<F>(draw: F) -> Vec<u16>
+where
+    F: FnOnce(&mut ST7735<MockSpi, MockDc, MockPin>),
+{
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    {
+        let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+        let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+        display.init(&mut MockDelay).unwrap();
+        draw(&mut display);
+    }
+    gram
+}
+
+#[test]
+fn set_pixels_clips_a_window_ending_at_u16_max() {
+    let gram = draw_on_panel(|display| {
+        display.set_pixels(0, 1, u16::MAX, 1, repeat_n(0xFFFF, 3)).unwrap();
+    });
+    assert_eq!(&gram[132..136], &[0xFFFF, 0xFFFF, 0xFFFF, 0]);
+}
+
+#[cfg(feature = "graphics")]
+#[test]
+fn fill_solid_clips_an_area_ending_at_u16_max() {
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::Rectangle;
+
+    let gram = draw_on_panel(|display| {
+        let area = Rectangle::new(Point::new(0, 1), Size::new(u16::MAX as u32 + 1, 1));
+        display.fill_solid(&area, Rgb565::WHITE).unwrap();
+    });
+    assert!(gram[132..260].iter().all(|&pixel| pixel == 0xFFFF));
+    assert_eq!(gram[260], 0);
+}
+
+#[cfg(feature = "primitives")]
+#[test]
+fn primitives_clip_shapes_reaching_past_u16_max() {
+    let gram = draw_on_panel(|display| {
+        display.fill_rect(u16::MAX - 1, 0, 10, 10, 0xFFFF).unwrap();
+        display.draw_rect(u16::MAX - 1, u16::MAX - 1, 10, 10, 0xFFFF).unwrap();
+        display.draw_image(u16::MAX, 0, 4, 1, &[0xFFFF; 4]).unwrap();
+    });
+    assert!(gram.iter().all(|&pixel| pixel == 0));
+}
+
+#[cfg(feature = "primitives")]
+#[test]
+fn draw_image_keeps_its_row_stride_when_cut_off() {
+    let width = u16::MAX - 2;
+    let mut image = vec![0u16; width as usize * 2];
+    image[0] = 0xF800;
+    image[width as usize] = 0x07E0;
+    let gram = draw_on_panel(|display| {
+        display.draw_image(3, 0, width, 2, &image).unwrap();
+    });
+    assert_eq!(gram[3], 0xF800);
+    assert_eq!(gram[132 + 3], 0x07E0);
+}
+
+#[cfg(feature = "primitives")]
+#[test]
+fn primitives_past_u16_max_fail_under_the_error_policy() {
+    use st7735_lcd::{BoundsPolicy, Error};
+
+    draw_on_panel(|display| {
+        display.set_bounds_policy(BoundsPolicy::Error);
+        assert_eq!(display.fill_rect(u16::MAX, 0, 2, 1, 0xFFFF), Err(Error::OutOfBounds));
+        assert_eq!(display.draw_rect(0, u16::MAX, 1, 2, 0xFFFF), Err(Error::OutOfBounds));
+        assert_eq!(display.draw_image(u16::MAX, 0, 2, 1, &[0; 2]), Err(Error::OutOfBounds));
+    });
+}
+
+#[cfg(feature = "buffered")]
+#[test]
+fn frame_buffer_set_pixels_accepts_a_window_ending_at_u16_max() {
+    use st7735_lcd::framebuffer::FrameBuffer;
+    use st7735_lcd::region::Region;
+
+    draw_on_panel(|display| {
+        let mut buffer = vec![0u16; 128 * 160];
+        let mut framebuffer = FrameBuffer::new(display, &mut buffer);
+        framebuffer.flush().unwrap();
+        framebuffer.set_pixels(0, 2, u16::MAX, 2, repeat_n(0xFFFF, 3));
+        assert_eq!(framebuffer.dirty(), Some(Region::new(0, 2, 128, 1)));
+    });
+}
+
+#[test]
+fn hybrid_set_pixels_accepts_a_window_ending_at_u16_max() {
+    use st7735_lcd::hybrid::Hybrid;
+
+    let gram = draw_on_panel(|display| {
+        let mut buffer = vec![0u16; 128 * 160];
+        let mut hybrid = Hybrid::new(display, &mut buffer, 128, 160);
+        hybrid.set_pixels(0, 1, u16::MAX, 1, repeat_n(0xFFFF, 200)).unwrap();
+    });
+    assert!(gram[132..260].iter().all(|&pixel| pixel == 0xFFFF));
+}
+
+#[test]
+fn text_past_u16_max_is_skipped() {
+    use st7735_lcd::font::{TextStyle, FONT_6X8};
+
+    let gram = draw_on_panel(|display| {
+        let plain = TextStyle::new(&FONT_6X8, 0xFFFF);
+        let filled = plain.background(0xFFFF);
+        assert_eq!(display.draw_text(u16::MAX - 8, 0, "abc", &plain).unwrap(), u16::MAX);
+        display.draw_text(u16::MAX - 8, 0, "abc", &filled).unwrap();
+        display.draw_text(0, u16::MAX - 3, "abc", &filled).unwrap();
+    });
+    assert!(gram.iter().all(|&pixel| pixel == 0));
+}
@@ -0,0 +1,84 @@
+//! Checks region views on the simulated panel.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "testing")]
+
+use core::cell::RefCell;
+
+use st7735_lcd::region::{Region, Rotation};
+use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+use st7735_lcd::ST7735;
+
+const RAMWR: u8 = 0x2C;
+
+const ROTATIONS: [Rotation; 4] = [Rotation::Deg0, Rotation::Deg90, Rotation::Deg180, Rotation::Deg270];
+
+#[test]
+fn flush_matches_pixel_by_pixel_drawing_in_every_rotation() {
+    let region = Region::new(10, 20, 5, 3);
+    for &rotation in ROTATIONS.iter() {
+        let mut expected = vec![0u16; GRAM_PIXELS];
+        let mut gram = vec![0u16; GRAM_PIXELS];
+        let mut log = vec![0u8; 4096];
+        let mut scratch = vec![0u8; 4096];
+        let reference = RefCell::new(MockPanel::new(&mut expected, &mut scratch));
+        let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+        let mut slow = ST7735::new_mock(&reference, true, false, 128, 160);
+        let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+        slow.init(&mut MockDelay).unwrap();
+        display.init(&mut MockDelay).unwrap();
+        panel.borrow_mut().clear_commands();
+
+        let colors: Vec<u16> = (1..=15).collect();
+        let mut view = slow.region(region).rotated(rotation);
+        let (width, _) = view.size();
+        for (i, &color) in colors.iter().enumerate() {
+            view.set_pixel(i as u16 % width, i as u16 / width, color).unwrap();
+        }
+        display.region(region).rotated(rotation).flush(&colors).unwrap();
+
+        let commands = panel.borrow().commands().to_vec();
+        assert_eq!(commands.iter().filter(|&&c| c == RAMWR).count(), 1, "{:?}", rotation);
+        assert_eq!(panel.borrow().gram(), reference.borrow().gram(), "{:?}", rotation);
+    }
+}
+
+#[test]
+fn flush_rejects_buffers_of_the_wrong_size() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    assert!(display.region(Region::new(0, 0, 2, 2)).flush(&[0; 3]).is_err());
+}
+
+#[test]
+fn regions_reaching_u16_max_clip_instead_of_overflowing() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    for &rotation in ROTATIONS.iter() {
+        let mut view = display.region(Region::new(u16::MAX - 4, u16::MAX - 4, 10, 10)).rotated(rotation);
+        view.set_pixel(3, 3, 0xFFFF).unwrap();
+        view.set_pixels(0, 0, 9, 9, core::iter::repeat(0xFFFF)).unwrap();
+        view.clear(0xFFFF).unwrap();
+    }
+    assert!(panel.borrow().gram().iter().all(|&pixel| pixel == 0));
+}
+
+#[test]
+fn set_pixels_accepts_a_window_ending_at_u16_max() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    let mut view = display.region(Region::new(0, 0, 4, 2));
+    view.set_pixels(0, 1, u16::MAX, 1, core::iter::repeat_n(0xFFFF, 5)).unwrap();
+    let panel = panel.borrow();
+    assert_eq!((0..5).map(|x| panel.pixel(x, 1)).collect::<Vec<_>>(), [0xFFFF, 0xFFFF, 0xFFFF, 0xFFFF, 0]);
+}
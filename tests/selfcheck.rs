@@ -0,0 +1,76 @@
+//! Checks the settings `probe` recommends.
+//!
+//! Run on the host with `cargo test --tests --target x86_64-unknown-linux-gnu`.
+
+use core::convert::Infallible;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use st7735_lcd::variant::Variant;
+use st7735_lcd::{NoResetPin, Orientation, ST7735};
+
+/// A bus with nothing attached: writes go nowhere and reads return zeros.
+struct IdleSpi;
+
+impl spi::Write<u8> for IdleSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, _words: &[u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl spi::Transfer<u8> for IdleSpi {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        words.fill(0);
+        Ok(words)
+    }
+}
+
+struct IdlePin;
+
+impl OutputPin for IdlePin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+struct Delay;
+
+impl DelayMs<u8> for Delay {
+    fn delay_ms(&mut self, _ms: u8) {}
+}
+
+fn display(width: u16, height: u16) -> ST7735<IdleSpi, IdlePin, NoResetPin> {
+    ST7735::new(IdleSpi, IdlePin, NoResetPin::new(), false, false, width, height)
+}
+
+#[test]
+fn probe_offsets_follow_the_panel_size() {
+    assert_eq!(display(128, 160).probe(&mut Delay).unwrap().offset, (2, 1));
+    assert_eq!(display(128, 128).probe(&mut Delay).unwrap().offset, (2, 3));
+    assert_eq!(display(80, 160).probe(&mut Delay).unwrap().offset, (26, 1));
+    assert_eq!(display(100, 150).probe(&mut Delay).unwrap().offset, (16, 6));
+}
+
+#[test]
+fn probe_offsets_follow_the_variant_and_orientation() {
+    let mut display = ST7735::with_variant(IdleSpi, IdlePin, NoResetPin::new(), Variant::RedTab);
+    assert_eq!(display.probe(&mut Delay).unwrap().offset, (0, 0));
+
+    let mut display = ST7735::with_variant(IdleSpi, IdlePin, NoResetPin::new(), Variant::GreenTab128x128);
+    display.set_orientation(&Orientation::Landscape).unwrap();
+    let report = display.probe(&mut Delay).unwrap();
+    assert!(!report.readback);
+    assert_eq!(report.offset, (3, 2));
+}
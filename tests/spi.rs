@@ -0,0 +1,287 @@
+//! Checks the exact bus traffic of the driver against embedded-hal-mock.
+//!
+//! Run on the host with `cargo test --tests --target x86_64-unknown-linux-gnu`.
+
+use embedded_hal_mock::delay::MockNoop;
+use embedded_hal_mock::pin::{Mock as PinMock, State, Transaction as PinTransaction};
+use embedded_hal_mock::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+
+use st7735_lcd::flush::Flush;
+use st7735_lcd::variant::Variant;
+use st7735_lcd::{Error, Orientation, ST7735};
+
+/// One write on the bus, with the DC level it is made at.
+enum Write {
+    Command(u8),
+    Data(&'static [u8]),
+}
+
+use Write::{Command, Data};
+
+/// Builds SPI and DC mocks expecting `writes` in order.
+fn bus(writes: &[Write]) -> (SpiMock, PinMock) {
+    let mut spi = Vec::new();
+    let mut dc = Vec::new();
+    for write in writes {
+        match write {
+            Command(command) => {
+                dc.push(PinTransaction::set(State::Low));
+                spi.push(SpiTransaction::write(vec![*command]));
+            }
+            Data(data) => {
+                dc.push(PinTransaction::set(State::High));
+                spi.push(SpiTransaction::write(data.to_vec()));
+            }
+        }
+    }
+    (SpiMock::new(&spi), PinMock::new(&dc))
+}
+
+/// Returns a reset pin mock expecting no transactions.
+fn idle_rst() -> PinMock {
+    PinMock::new(&[])
+}
+
+#[test]
+fn init_sends_the_built_in_sequence() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x01),
+        Command(0x11),
+        Command(0xB1),
+        Data(&[0x01, 0x2C, 0x2D]),
+        Command(0xB2),
+        Data(&[0x01, 0x2C, 0x2D]),
+        Command(0xB3),
+        Data(&[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D]),
+        Command(0xB4),
+        Data(&[0x07]),
+        Command(0xC0),
+        Data(&[0xA2, 0x02, 0x84]),
+        Command(0xC1),
+        Data(&[0xC5]),
+        Command(0xC2),
+        Data(&[0x0A, 0x00]),
+        Command(0xC3),
+        Data(&[0x8A, 0x2A]),
+        Command(0xC4),
+        Data(&[0x8A, 0xEE]),
+        Command(0xC5),
+        Data(&[0x0E]),
+        Command(0x20),
+        Command(0x36),
+        Data(&[0x00]),
+        Command(0x3A),
+        Data(&[0x05]),
+        Command(0x29),
+    ]);
+    let mut rst = PinMock::new(&[
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), rst.clone(), true, false, 128, 160);
+    display.init(&mut MockNoop::new()).unwrap();
+    spi.done();
+    dc.done();
+    rst.done();
+}
+
+#[test]
+fn set_orientation_writes_madctl_and_swaps_size() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x36),
+        Data(&[0x68]),
+        Command(0x36),
+        Data(&[0xC8]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), false, false, 128, 160);
+    display.set_orientation(&Orientation::Landscape).unwrap();
+    assert_eq!(display.size(), (160, 128));
+    display.set_orientation(&Orientation::PortraitSwapped).unwrap();
+    assert_eq!(display.size(), (128, 160));
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn set_address_window_adds_the_offset() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x2A),
+        Data(&[0x00, 0x04]),
+        Data(&[0x00, 0x83]),
+        Command(0x2B),
+        Data(&[0x00, 0x0A]),
+        Data(&[0x01, 0x09]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 132, 300);
+    display.set_offset(2, 1);
+    display.set_address_window(2, 9, 129, 264).unwrap();
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn set_address_window_uses_the_orientation_offsets() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x36),
+        Data(&[0x68]),
+        Command(0x2A),
+        Data(&[0x00, 0x01]),
+        Data(&[0x00, 0x01]),
+        Command(0x2B),
+        Data(&[0x00, 0x02]),
+        Data(&[0x00, 0x02]),
+    ]);
+    let mut display = ST7735::with_variant(spi.clone(), dc.clone(), idle_rst(), Variant::GreenTab);
+    display.set_orientation(&Orientation::Landscape).unwrap();
+    display.set_address_window(0, 0, 0, 0).unwrap();
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn set_pixels_sends_big_endian_rgb565() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x2A),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x02]),
+        Command(0x2B),
+        Data(&[0x00, 0x05]),
+        Data(&[0x00, 0x05]),
+        Command(0x2C),
+        Data(&[0xF8, 0x00, 0x07, 0xE0, 0x00, 0x1F]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    display.set_pixels(0, 5, 2, 5, [0xF800, 0x07E0, 0x001F]).unwrap();
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn set_pixels_clips_to_the_panel() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x2A),
+        Data(&[0x00, 0x7F]),
+        Data(&[0x00, 0x7F]),
+        Command(0x2B),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x00]),
+        Command(0x2C),
+        Data(&[0x12, 0x34]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    display.set_pixels(127, 0, 128, 0, [0x1234, 0x5678]).unwrap();
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn abandoned_flush_leaves_the_next_draw_a_fresh_window() {
+    let (mut spi, mut dc) = bus(&[
+        Command(0x2A),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x03]),
+        Command(0x2B),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x01]),
+        Command(0x2C),
+        Data(&[0x00, 0x01, 0x00, 0x02]),
+        Command(0x2A),
+        Data(&[0x00, 0x0A]),
+        Data(&[0x00, 0x0A]),
+        Command(0x2B),
+        Data(&[0x00, 0x14]),
+        Data(&[0x00, 0x14]),
+        Command(0x2C),
+        Data(&[0xAB, 0xCD]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    let pixels = [1, 2, 3, 4, 5, 6, 7, 8];
+    {
+        // Abandoned after the first chunk.
+        let mut flush = Flush::new(0, 0, 3, 1, &pixels).chunk_size(2);
+        assert_eq!(display.poll_flush(&mut flush), Err(nb::Error::WouldBlock));
+    }
+    display.set_pixels(10, 20, 10, 20, [0xABCD]).unwrap();
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn flush_reopens_its_window_after_an_interleaved_draw() {
+    let (mut spi, mut dc) = bus(&[
+        // First chunk, into the whole window
+        Command(0x2A),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x03]),
+        Command(0x2B),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x01]),
+        Command(0x2C),
+        Data(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x03]),
+        // Interleaved pixel
+        Command(0x2A),
+        Data(&[0x00, 0x0A]),
+        Data(&[0x00, 0x0A]),
+        Command(0x2B),
+        Data(&[0x00, 0x0A]),
+        Data(&[0x00, 0x0A]),
+        Command(0x2C),
+        Data(&[0xFF, 0xFF]),
+        // Rest of the first row
+        Command(0x2A),
+        Data(&[0x00, 0x03]),
+        Data(&[0x00, 0x03]),
+        Command(0x2B),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x00]),
+        Command(0x2C),
+        Data(&[0x00, 0x04]),
+        // Remaining rows
+        Command(0x2A),
+        Data(&[0x00, 0x00]),
+        Data(&[0x00, 0x03]),
+        Command(0x2B),
+        Data(&[0x00, 0x01]),
+        Data(&[0x00, 0x01]),
+        Command(0x2C),
+        Data(&[0x00, 0x05, 0x00, 0x06, 0x00, 0x07]),
+        Data(&[0x00, 0x08]),
+    ]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    let pixels = [1, 2, 3, 4, 5, 6, 7, 8];
+    let mut flush = Flush::new(0, 0, 3, 1, &pixels).chunk_size(3);
+    assert_eq!(display.poll_flush(&mut flush), Err(nb::Error::WouldBlock));
+    display.set_pixel(10, 10, 0xFFFF).unwrap();
+    assert_eq!(display.poll_flush(&mut flush), Err(nb::Error::WouldBlock));
+    assert_eq!(display.poll_flush(&mut flush), Err(nb::Error::WouldBlock));
+    assert_eq!(display.poll_flush(&mut flush), Ok(()));
+    assert!(flush.is_done());
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn flush_of_an_inverted_window_is_empty() {
+    let (mut spi, mut dc) = bus(&[]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    let pixels = [1, 2, 3, 4];
+    let mut flush = Flush::new(3, 0, 2, 1, &pixels);
+    assert!(flush.is_done());
+    assert_eq!(display.poll_flush(&mut flush), Ok(()));
+    let mut flush = Flush::new(0, 1, 1, 0, &pixels);
+    assert_eq!(display.poll_flush(&mut flush), Ok(()));
+    spi.done();
+    dc.done();
+}
+
+#[test]
+fn set_address_window_rejects_offsets_past_u16_max() {
+    let (mut spi, mut dc) = bus(&[]);
+    let mut display = ST7735::new(spi.clone(), dc.clone(), idle_rst(), true, false, 128, 160);
+    display.set_offset(2, 1);
+    assert_eq!(display.set_address_window(0, 0, u16::MAX, 0), Err(Error::OutOfBounds));
+    assert_eq!(display.set_address_window(0, u16::MAX, 0, u16::MAX), Err(Error::OutOfBounds));
+    spi.done();
+    dc.done();
+}
@@ -0,0 +1,42 @@
+//! Checks strip transfers on the simulated panel.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "testing")]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use st7735_lcd::strip::{StripBuffers, StripSink};
+use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+use st7735_lcd::{Error, ST7735};
+
+#[test]
+fn strips_shorter_than_a_pixel_are_rejected() {
+    struct Sink;
+
+    impl StripSink for Sink {
+        type Error = Infallible;
+
+        fn start(&mut self, _bytes: &[u8]) -> Result<(), Infallible> {
+            panic!("nothing should be sent");
+        }
+
+        fn wait(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    panel.borrow_mut().clear_commands();
+    let (mut front, mut back) = ([0u8; 64], [0u8; 1]);
+    let mut strips = StripBuffers::new(&mut front, &mut back);
+    let result = display.write_strips(0, 0, 9, 9, &mut strips, &mut Sink, |_, out: &mut [u16]| out.len());
+    assert_eq!(result, Err(Error::InvalidArgument));
+    assert!(panel.borrow().commands().is_empty());
+}
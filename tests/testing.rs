@@ -0,0 +1,80 @@
+//! Checks the simulated panel against the commands the driver sends.
+//!
+//! Run on the host with
+//! `cargo test --tests --features testing --target x86_64-unknown-linux-gnu`.
+
+#![cfg(feature = "testing")]
+
+use core::cell::RefCell;
+
+use st7735_lcd::testing::{MockDelay, MockPanel, GRAM_PIXELS};
+use st7735_lcd::{Orientation, PixelFormat, ST7735};
+
+const CASET: u8 = 0x2A;
+const RASET: u8 = 0x2B;
+const RAMWR: u8 = 0x2C;
+const MADCTL: u8 = 0x36;
+const COLMOD: u8 = 0x3A;
+
+#[test]
+fn init_and_set_orientation_update_madctl_and_colmod() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, false, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    assert_eq!(panel.borrow().madctl(), 0x08);
+    assert_eq!(panel.borrow().colmod(), 0x05);
+    assert!(panel.borrow().commands().contains(&MADCTL));
+    assert!(panel.borrow().commands().contains(&COLMOD));
+
+    panel.borrow_mut().clear_commands();
+    display.set_orientation(&Orientation::Landscape).unwrap();
+    assert_eq!(panel.borrow().commands(), &[MADCTL]);
+    assert_eq!(panel.borrow().madctl(), 0x68);
+
+    // Landscape exchanges rows and columns and mirrors the columns.
+    display.set_pixel(5, 0, 0xF800).unwrap();
+    assert_eq!(panel.borrow().pixel(131, 5), 0xF800);
+}
+
+#[test]
+fn set_pixels_fills_exactly_its_window() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    panel.borrow_mut().clear_commands();
+
+    display.set_pixels(2, 3, 4, 5, core::iter::repeat_n(0x07E0, 9)).unwrap();
+    assert_eq!(panel.borrow().commands(), &[CASET, RASET, RAMWR]);
+    let panel = panel.borrow();
+    for y in 2..=6 {
+        for x in 1..=5 {
+            let inside = (2..=4).contains(&x) && (3..=5).contains(&y);
+            assert_eq!(panel.pixel(x, y), if inside { 0x07E0 } else { 0 }, "({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn odd_length_rgb444_writes_keep_their_last_pixel() {
+    let mut gram = vec![0u16; GRAM_PIXELS];
+    let mut log = vec![0u8; 256];
+    let panel = RefCell::new(MockPanel::new(&mut gram, &mut log));
+    let mut display = ST7735::new_mock(&panel, true, false, 128, 160);
+    display.init(&mut MockDelay).unwrap();
+    display.set_pixel_format(PixelFormat::Rgb444).unwrap();
+    assert_eq!(panel.borrow().colmod(), 0x03);
+    panel.borrow_mut().clear_commands();
+
+    // Colors with no bits below RGB444 precision survive the round trip.
+    display.set_pixels(0, 0, 2, 0, [0xF800, 0x07E0, 0x001F]).unwrap();
+    assert_eq!(panel.borrow().commands(), &[CASET, RASET, RAMWR]);
+    let panel = panel.borrow();
+    assert_eq!(panel.pixel(0, 0), 0xF800);
+    assert_eq!(panel.pixel(1, 0), 0x07E0);
+    assert_eq!(panel.pixel(2, 0), 0x001F);
+    assert_eq!(panel.pixel(3, 0), 0);
+}